@@ -18,7 +18,7 @@ pub enum ErrorCode {
     NotWinner,
     #[msg("Bet has expired and can no longer be matched")]
     BetExpired,
-    #[msg("Settlement time must be at least 1 hour in the future")]
+    #[msg("Settlement time is closer than the market minimum duration")]
     SettlementTimeTooClose,
     #[msg("Oracle data is stale")]
     StaleOracleData,
@@ -44,4 +44,132 @@ pub enum ErrorCode {
     UnauthorizedCloser,
     #[msg("Invalid better account")]
     InvalidBetter,
+    #[msg("Refund account does not belong to the bet funder")]
+    InvalidFunder,
+    #[msg("The better or funder cannot match their own bet")]
+    SelfMatch,
+    #[msg("Matcher is not allowed on this market")]
+    MatcherNotAllowed,
+    #[msg("Market match filter account is missing or invalid")]
+    InvalidMatchFilter,
+    #[msg("Too many wallets in match filter")]
+    TooManyFilterWallets,
+    #[msg("Stake is below the market minimum")]
+    StakeTooSmall,
+    #[msg("Stake is above the market maximum")]
+    StakeTooLarge,
+    #[msg("Market open interest limit exceeded")]
+    OpenInterestLimitExceeded,
+    #[msg("Invalid market limits")]
+    InvalidMarketLimits,
+    #[msg("Settlement time is further out than the market maximum duration")]
+    SettlementTimeTooFar,
+    #[msg("Settlement time is not on one of the market's settlement slots")]
+    SettlementTimeNotOnSlot,
+    #[msg("Invalid settlement bounds")]
+    InvalidSettlementBounds,
+    #[msg("Bet settles on a scheduled slot and needs that slot's settlement round")]
+    SettlementRoundRequired,
+    #[msg("Settlement round does not match the bet")]
+    InvalidSettlementRound,
+    #[msg("Price was published before the settlement slot")]
+    PriceBeforeSettlementSlot,
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Market is already in the registry")]
+    MarketAlreadyRegistered,
+    #[msg("Market is not in the registry")]
+    MarketNotRegistered,
+    #[msg("Market registry is full")]
+    MarketRegistryFull,
+    #[msg("Market is not accepting new bets")]
+    MarketNotActive,
+    #[msg("Market must be close-only before it can be closed")]
+    MarketNotCloseOnly,
+    #[msg("Market still has open bets")]
+    MarketHasOpenBets,
+    #[msg("Memo is longer than 64 bytes")]
+    MemoTooLong,
+    #[msg("Memo contains invalid characters")]
+    InvalidMemo,
+    #[msg("Account type cannot be migrated")]
+    UnsupportedAccount,
+    #[msg("Range bets need a low bound below the high bound")]
+    InvalidPriceRange,
+    #[msg("Percentage moves must be positive, and down moves below 100%")]
+    InvalidPriceMove,
+    #[msg("Bet is not a touch bet")]
+    NotTouchBet,
+    #[msg("Price update is outside the window between match and settlement")]
+    PriceOutsideBetWindow,
+    #[msg("Price update has not crossed the barrier")]
+    BarrierNotCrossed,
+    #[msg("Price update is not fully verified")]
+    UnverifiedPriceUpdate,
+    #[msg("Relative-performance bets need a second, different market")]
+    InvalidOtherMarket,
+    #[msg("TWAP window and minimum samples must both be set or both be zero")]
+    InvalidTwapSettings,
+    #[msg("Market does not average settlement rounds")]
+    TwapDisabled,
+    #[msg("Price is outside the round's TWAP window or too close to the last sample")]
+    InvalidTwapSample,
+    #[msg("Settlement round TWAP is not complete yet")]
+    TwapIncomplete,
+    #[msg("Pools only support bets that settle on the settlement price alone")]
+    UnsupportedPoolDirection,
+    #[msg("Pool has already been settled")]
+    PoolAlreadySettled,
+    #[msg("Pool has not been settled yet")]
+    PoolNotSettled,
+    #[msg("Bucket pools need 2 to 16 buckets with positive, strictly increasing boundaries")]
+    InvalidBuckets,
+    #[msg("Bucket does not exist in this pool")]
+    InvalidBucket,
+    #[msg("Parlays need 2 to 8 legs that settle on the settlement price alone")]
+    InvalidParlayLegs,
+    #[msg("Leg does not exist in this parlay or is on another market")]
+    InvalidParlayLeg,
+    #[msg("Parlay leg has already been priced")]
+    ParlayLegAlreadySettled,
+    #[msg("Only self-funded bets on a fixed condition can recur, with a positive interval and round count")]
+    InvalidRecurrence,
+    #[msg("Bet does not have another round to roll over")]
+    BetDoesNotRecur,
+    #[msg("Signer is not a party to this bet")]
+    NotBetParty,
+    #[msg("Offers only support bets on a fixed condition")]
+    UnsupportedOfferDirection,
+    #[msg("Amount exceeds the offer's remaining capacity")]
+    OfferCapacityExceeded,
+    #[msg("Bet can only be voided once it has failed to settle for the void delay")]
+    VoidTooEarly,
+    #[msg("Market does not match the bet")]
+    InvalidMarket,
+    #[msg("Price was published too long after the settlement slot")]
+    PriceAfterSettlementSlot,
+    #[msg("Open bet count is inconsistent with the market's bets")]
+    InvalidOpenBetCount,
+    #[msg("Market's open bet count is already tracked")]
+    OpenBetCountAlreadyTracked,
+    #[msg("Market's open bet count must be backfilled before it can be closed")]
+    OpenBetCountNotBackfilled,
+    #[msg("Account is in an older layout and must be migrated with migrate_account first")]
+    AccountNeedsMigration,
+    #[msg("Bet's recurrence schedule doesn't match what the matcher agreed to")]
+    RecurrenceMismatch,
+    #[msg("Recurring bet can't be rolled over until its parties have had time to stop it")]
+    RolloverTooEarly,
+    #[msg("Offer capacity must be greater than zero")]
+    InvalidOfferCapacity,
+    #[msg("Settlement round already has all its samples")]
+    SettlementRoundComplete,
+    #[msg("Token account is required to receive this payout")]
+    TokenAccountRequired,
+    #[msg("Pool can only be voided once it has failed to settle for the void delay")]
+    PoolVoidTooEarly,
+    #[msg("Fee recipient token account does not belong to the market's fee claimer")]
+    InvalidFeeRecipient,
+    #[msg("Recurring bet must be rolled over or stopped before it can be claimed")]
+    RolloverPending,
 }
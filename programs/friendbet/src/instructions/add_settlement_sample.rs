@@ -0,0 +1,62 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::{normalize_price, verified_price};
+use crate::state::{BettingMarket, MarketLoader, SettlementRound};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+/// Adds another price to a settlement round on a market that averages rounds
+/// over a TWAP window. Anyone can call it.
+#[derive(Accounts)]
+pub struct AddSettlementSample<'info> {
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"round", market.key().as_ref(), &settlement_round.settlement_time.to_le_bytes()],
+        bump = settlement_round.bump
+    )]
+    pub settlement_round: Account<'info, SettlementRound>,
+
+    /// Any fully verified Pyth update for the market's feed, so samples that
+    /// were missed can be filled in later from historical updates
+    pub price_update: Account<'info, PriceUpdateV2>,
+}
+
+pub fn add_settlement_sample(ctx: Context<AddSettlementSample>) -> Result<()> {
+    let market = ctx.accounts.market.load_market()?;
+    require!(market.twap_window != 0, ErrorCode::TwapDisabled);
+
+    let pyth_price = verified_price(&ctx.accounts.price_update, &market)?;
+
+    // Once bets can settle on the round its average is final
+    let settlement_round = &mut ctx.accounts.settlement_round;
+    require!(
+        settlement_round.sample_count < market.twap_min_samples
+            && !settlement_round.is_complete(&market, Clock::get()?.unix_timestamp),
+        ErrorCode::SettlementRoundComplete
+    );
+
+    // Samples must be spread across the window after the slot
+    require!(
+        pyth_price.publish_time >= settlement_round.publish_time + market.twap_sample_spacing()
+            && pyth_price.publish_time
+                <= settlement_round.settlement_time + market.twap_window as i64,
+        ErrorCode::InvalidTwapSample
+    );
+
+    settlement_round.add_sample(normalize_price(&pyth_price)?, pyth_price.publish_time);
+
+    msg!(
+        "Settlement round sample {} for {} at {}: average {}",
+        settlement_round.sample_count,
+        market.get_token_name(),
+        settlement_round.settlement_time,
+        settlement_round.price
+    );
+
+    Ok(())
+}
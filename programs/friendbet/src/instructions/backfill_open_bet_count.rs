@@ -0,0 +1,43 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct BackfillOpenBetCount<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+}
+
+/// Set the open bet count of a market migrated from before it was tracked to
+/// the number of its bet, pool position, parlay and offer accounts still open.
+/// The market can't be closed until this is done.
+pub fn backfill_open_bet_count(ctx: Context<BackfillOpenBetCount>, count: u64) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    require!(
+        market.open_bets_untracked != 0,
+        ErrorCode::OpenBetCountAlreadyTracked
+    );
+    // Everything opened since migration is already counted, and at most
+    // `bet_count` bets can have been opened before it
+    require!(
+        count >= market.open_bet_count
+            && count <= market.open_bet_count.saturating_add(market.bet_count),
+        ErrorCode::InvalidOpenBetCount
+    );
+    market.open_bet_count = count;
+    market.open_bets_untracked = 0;
+
+    msg!(
+        "Open bet count for {} backfilled to {}",
+        market.get_token_name(),
+        count
+    );
+
+    Ok(())
+}
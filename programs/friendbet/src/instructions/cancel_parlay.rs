@@ -0,0 +1,114 @@
+use crate::errors::ErrorCode;
+use crate::instructions::create_parlay::leg_markets;
+use crate::state::{MarketLoader, Parlay};
+use crate::ADMIN;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use std::str::FromStr;
+
+#[event]
+pub struct ParlayCancelled {
+    pub parlay: Pubkey,
+    pub better: Pubkey,
+    pub closer: Pubkey,
+    pub refund_amount: u64,
+}
+
+/// Refunds and closes an unmatched parlay. The legs' markets are passed as
+/// writable remaining accounts, in leg order.
+#[derive(Accounts)]
+pub struct CancelParlay<'info> {
+    /// CHECK: The account of the original bettor.
+    #[account(mut)]
+    pub better: UncheckedAccount<'info>,
+
+    /// The account cancelling the parlay. Must be either the original 'better' or the admin.
+    pub closer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"parlay", parlay.better.as_ref(), &parlay.parlay_id.to_le_bytes()],
+        bump = parlay.bump,
+        constraint = parlay.better == better.key() @ ErrorCode::InvalidBetter,
+        constraint = !parlay.is_matched @ ErrorCode::BetAlreadyMatched,
+        close = better
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    #[account(
+        mut,
+        constraint = parlay_escrow.key() == parlay.escrow
+    )]
+    pub parlay_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = refund_token_account.owner == parlay.better @ ErrorCode::InvalidBetter
+    )]
+    pub refund_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn cancel_parlay<'info>(ctx: Context<'_, '_, 'info, 'info, CancelParlay<'info>>) -> Result<()> {
+    let is_original_better = ctx.accounts.closer.key() == ctx.accounts.better.key();
+    let is_admin = ctx.accounts.closer.key() == Pubkey::from_str(ADMIN).unwrap();
+    if !is_original_better && !is_admin {
+        return err!(ErrorCode::UnauthorizedCloser);
+    }
+
+    let parlay = &ctx.accounts.parlay;
+    for market in leg_markets(ctx.remaining_accounts, &parlay.legs)? {
+        let mut market = market.load_market_mut()?;
+        market.remove_open_interest(parlay.amount);
+        market.remove_open_bet()?;
+    }
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"parlay".as_ref(),
+        parlay.better.as_ref(),
+        &parlay.parlay_id.to_le_bytes(),
+        &[parlay.bump],
+    ]];
+
+    let refund_amount = ctx.accounts.parlay_escrow.amount;
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.parlay_escrow.to_account_info(),
+                to: ctx.accounts.refund_token_account.to_account_info(),
+                authority: parlay.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        refund_amount,
+    )?;
+
+    // Return the escrow's rent along with the parlay's
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.parlay_escrow.to_account_info(),
+            destination: ctx.accounts.better.to_account_info(),
+            authority: parlay.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(ParlayCancelled {
+        parlay: parlay.key(),
+        better: parlay.better,
+        closer: ctx.accounts.closer.key(),
+        refund_amount,
+    });
+
+    msg!(
+        "Parlay cancelled by {}: {} USDC returned to {}",
+        ctx.accounts.closer.key(),
+        refund_amount / 1_000_000,
+        parlay.better
+    );
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::errors::ErrorCode;
+use crate::instructions::claim_pool::{close_pool_escrow, pay_out_position};
+use crate::state::{BettingMarket, BucketPool, BucketPosition, MarketLoader, UserProfile};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[event]
+pub struct BucketPoolClaimed {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub payout_amount: u64,
+    pub fee_amount: u64,
+}
+
+/// Bucket pool counterpart of `ClaimPool`: pays out a position and closes it,
+/// and the escrow with the last one.
+#[derive(Accounts)]
+pub struct ClaimBucketPool<'info> {
+    /// CHECK: Already checked position.owner == owner.key()
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bucket_position", pool.key().as_ref(), &position.position_index.to_le_bytes()],
+        bump = position.bump,
+        constraint = position.owner == owner.key(),
+        close = owner
+    )]
+    pub position: Account<'info, BucketPosition>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bucket_pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &BucketPool::boundaries_seed(&pool.boundaries)
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BucketPool>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        constraint = pool_escrow.key() == pool.escrow
+    )]
+    pub pool_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == owner.key()
+    )]
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == market.load_market()?.fee_claimer
+    )]
+    pub fee_recipient_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump = owner_profile.bump
+    )]
+    pub owner_profile: Account<'info, UserProfile>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_bucket_pool(ctx: Context<ClaimBucketPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let position = &ctx.accounts.position;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    require!(pool.is_settled, ErrorCode::PoolNotSettled);
+    let last_position = pool.close_position();
+
+    let settlement_time = pool.settlement_time.to_le_bytes();
+    let boundaries_seed = BucketPool::boundaries_seed(&pool.boundaries);
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"bucket_pool".as_ref(),
+        pool.market.as_ref(),
+        &settlement_time,
+        &boundaries_seed,
+        &[pool.bump],
+    ]];
+
+    let (payout_amount, fee_amount) = pay_out_position(
+        &mut market,
+        &mut ctx.accounts.owner_profile,
+        position.amount,
+        pool.payout(position.bucket, position.amount),
+        pool.winning_bucket.is_none(),
+        pool.to_account_info(),
+        signer_seeds,
+        &ctx.accounts.pool_escrow,
+        ctx.accounts.owner_token_account.as_ref(),
+        ctx.accounts.fee_recipient_token_account.as_ref(),
+        &ctx.accounts.token_program,
+    )?;
+
+    if last_position {
+        close_pool_escrow(
+            &mut market,
+            pool.to_account_info(),
+            signer_seeds,
+            &mut ctx.accounts.pool_escrow,
+            ctx.accounts.fee_recipient_token_account.as_ref(),
+            ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    emit!(BucketPoolClaimed {
+        pool: pool.key(),
+        position: position.key(),
+        owner: position.owner,
+        payout_amount,
+        fee_amount,
+    });
+
+    msg!(
+        "Bucket pool position claimed by {}: {} USDC (with {} USDC fee) on {}",
+        position.owner,
+        payout_amount / 1_000_000,
+        fee_amount / 1_000_000,
+        market.get_token_name()
+    );
+
+    Ok(())
+}
@@ -1,11 +1,23 @@
 use crate::errors::ErrorCode;
-use crate::state::{Bet, BettingMarket, FEE_DENOMINATOR, FEE_PERCENTAGE};
+use crate::state::{
+    Bet, BettingMarket, MarketLoader, UserProfile, FEE_DENOMINATOR, FEE_PERCENTAGE,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct FundsClaimed {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub winner: Pubkey,
+    pub claimer: Pubkey,
+    pub winner_amount: u64,
+    pub fee_amount: u64,
+}
 
 #[derive(Accounts)]
 pub struct ClaimFunds<'info> {
-    /// CHECK: Already checked bet.winner.unwrap() == claimer.key()
+    /// CHECK: Already checked bet.payout_recipient() == claimer.key()
     #[account(mut)]
     pub claimer: AccountInfo<'info>,
 
@@ -13,22 +25,23 @@ pub struct ClaimFunds<'info> {
         mut,
         seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
         bump = bet.bump,
-        constraint = bet.winner.unwrap() == claimer.key(),
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration,
+        constraint = bet.payout_recipient() == Some(claimer.key()) @ ErrorCode::NotWinner,
+        constraint = !bet.recurs() @ ErrorCode::RolloverPending,
         close = claimer
     )]
     pub bet: Account<'info, Bet>,
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
         constraint = market.key() == bet.market
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
     #[account(
         mut,
-        close = claimer,
         constraint = bet_escrow.key() == bet.escrow
     )]
     pub bet_escrow: Account<'info, TokenAccount>,
@@ -42,17 +55,26 @@ pub struct ClaimFunds<'info> {
     // Fee recipient account
     #[account(
         mut,
-        constraint = fee_recipient_token_account.owner == market.fee_claimer
+        constraint = fee_recipient_token_account.owner == market.load_market()?.fee_claimer
     )]
     pub fee_recipient_token_account: Account<'info, TokenAccount>,
 
+    /// Profile of whoever receives the payout, which is a sponsor's rather
+    /// than the better's when the sponsor keeps the winnings
+    #[account(
+        mut,
+        seeds = [b"profile", claimer.key().as_ref()],
+        bump = claimer_profile.bump
+    )]
+    pub claimer_profile: Account<'info, UserProfile>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn claim_funds(ctx: Context<ClaimFunds>) -> Result<()> {
     let bet = &ctx.accounts.bet;
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
 
     // Ensure bet is settled
     require!(bet.is_settled, ErrorCode::BetNotSettled);
@@ -71,7 +93,10 @@ pub fn claim_funds(ctx: Context<ClaimFunds>) -> Result<()> {
     let winner_amount = escrow_balance.checked_sub(fee_amount).unwrap();
 
     // Update market stats for fees
-    market.total_fees_collected = market.total_fees_collected.checked_add(fee_amount).unwrap();
+    market.record_fees(Clock::get()?.unix_timestamp, fee_amount);
+    market.remove_open_interest(escrow_balance);
+    // The bet account is closed once claimed
+    market.remove_open_bet()?;
 
     // Create signing authority for the bet PDA
     let signer_seeds: &[&[&[u8]]] = &[&[
@@ -109,6 +134,30 @@ pub fn claim_funds(ctx: Context<ClaimFunds>) -> Result<()> {
         winner_amount,
     )?;
 
+    // Return the escrow's rent along with the bet's
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.bet_escrow.to_account_info(),
+            destination: ctx.accounts.claimer.to_account_info(),
+            authority: ctx.accounts.bet.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    ctx.accounts
+        .claimer_profile
+        .record_claim(winner_amount, fee_amount);
+
+    emit!(FundsClaimed {
+        bet: ctx.accounts.bet.key(),
+        market: ctx.accounts.market.key(),
+        winner: ctx.accounts.bet.winner.unwrap(),
+        claimer: ctx.accounts.claimer.key(),
+        winner_amount,
+        fee_amount,
+    });
+
     msg!(
         "Funds claimed by winner {}: {} USDC (with {} USDC fee) from bet on {}.",
         ctx.accounts.claimer.key(),
@@ -0,0 +1,153 @@
+use crate::errors::ErrorCode;
+use crate::instructions::create_parlay::leg_markets;
+use crate::state::{MarketLoader, Parlay, UserProfile, FEE_DENOMINATOR, FEE_PERCENTAGE};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct ParlayClaimed {
+    pub parlay: Pubkey,
+    pub winner: Pubkey,
+    pub winner_amount: u64,
+    pub fee_amount: u64,
+}
+
+/// Pays a settled parlay to its winner. The fee is split evenly across the
+/// legs' markets, so the legs' markets are passed as writable remaining
+/// accounts, in leg order, followed by a token account of each market's fee
+/// claimer, in the same order.
+#[derive(Accounts)]
+pub struct ClaimParlay<'info> {
+    /// CHECK: Already checked parlay.winner == claimer.key()
+    #[account(mut)]
+    pub claimer: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"parlay", parlay.better.as_ref(), &parlay.parlay_id.to_le_bytes()],
+        bump = parlay.bump,
+        constraint = parlay.winner == Some(claimer.key()) @ ErrorCode::NotWinner,
+        close = claimer
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    #[account(
+        mut,
+        constraint = parlay_escrow.key() == parlay.escrow
+    )]
+    pub parlay_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = claimer_token_account.owner == claimer.key()
+    )]
+    pub claimer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", claimer.key().as_ref()],
+        bump = claimer_profile.bump
+    )]
+    pub claimer_profile: Account<'info, UserProfile>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_parlay<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimParlay<'info>>) -> Result<()> {
+    let parlay = &ctx.accounts.parlay;
+
+    require!(parlay.is_settled, ErrorCode::BetNotSettled);
+
+    // Winner takes both stakes, less the usual fee
+    let escrow_balance = ctx.accounts.parlay_escrow.amount;
+    let fee_amount = escrow_balance
+        .checked_mul(FEE_PERCENTAGE)
+        .unwrap()
+        .checked_div(FEE_DENOMINATOR)
+        .unwrap();
+    let winner_amount = escrow_balance.checked_sub(fee_amount).unwrap();
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"parlay".as_ref(),
+        parlay.better.as_ref(),
+        &parlay.parlay_id.to_le_bytes(),
+        &[parlay.bump],
+    ]];
+
+    let transfer_from_escrow = |to: AccountInfo<'info>, amount: u64| {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.parlay_escrow.to_account_info(),
+                    to,
+                    authority: parlay.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )
+    };
+
+    // Each leg's market collects its share of the fee
+    let (market_accounts, fee_accounts) = ctx
+        .remaining_accounts
+        .split_at(parlay.legs.len().min(ctx.remaining_accounts.len()));
+    require!(
+        fee_accounts.len() == parlay.legs.len(),
+        ErrorCode::InvalidParlayLeg
+    );
+    let current_time = Clock::get()?.unix_timestamp;
+    for ((market, fee_account), fee_share) in leg_markets(market_accounts, &parlay.legs)?
+        .iter()
+        .zip(fee_accounts)
+        .zip(parlay.fee_shares(fee_amount))
+    {
+        let mut market = market.load_market_mut()?;
+        let fee_recipient_token_account = Account::<TokenAccount>::try_from(fee_account)?;
+        require_keys_eq!(
+            fee_recipient_token_account.owner,
+            market.fee_claimer,
+            ErrorCode::InvalidFeeRecipient
+        );
+        market.record_fees(current_time, fee_share);
+        if fee_share > 0 {
+            transfer_from_escrow(fee_recipient_token_account.to_account_info(), fee_share)?;
+        }
+    }
+
+    transfer_from_escrow(
+        ctx.accounts.claimer_token_account.to_account_info(),
+        winner_amount,
+    )?;
+    ctx.accounts
+        .claimer_profile
+        .record_claim(winner_amount, fee_amount);
+
+    // Return the escrow's rent along with the parlay's
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.parlay_escrow.to_account_info(),
+            destination: ctx.accounts.claimer.to_account_info(),
+            authority: parlay.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(ParlayClaimed {
+        parlay: parlay.key(),
+        winner: ctx.accounts.claimer.key(),
+        winner_amount,
+        fee_amount,
+    });
+
+    msg!(
+        "Parlay claimed by winner {}: {} USDC (with {} USDC fee)",
+        ctx.accounts.claimer.key(),
+        winner_amount / 1_000_000,
+        fee_amount / 1_000_000
+    );
+
+    Ok(())
+}
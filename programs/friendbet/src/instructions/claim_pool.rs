@@ -0,0 +1,259 @@
+use crate::errors::ErrorCode;
+use crate::state::{
+    BettingMarket, MarketLoader, Pool, PoolPosition, UserProfile, FEE_DENOMINATOR, FEE_PERCENTAGE,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct PoolClaimed {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub payout_amount: u64,
+    pub fee_amount: u64,
+}
+
+/// Pays out a position in a settled pool and closes it. Losing positions pay
+/// nothing but still need closing to release their rent and market count, so
+/// anyone can close them without token accounts. The last claim sweeps any
+/// rounding dust to the fee claimer and closes the escrow.
+#[derive(Accounts)]
+pub struct ClaimPool<'info> {
+    /// CHECK: Already checked position.owner == owner.key()
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_position", pool.key().as_ref(), &position.position_index.to_le_bytes()],
+        bump = position.bump,
+        constraint = position.owner == owner.key(),
+        close = owner
+    )]
+    pub position: Account<'info, PoolPosition>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &pool.price_threshold.to_le_bytes(),
+            &pool.price_direction.seed()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        constraint = pool_escrow.key() == pool.escrow
+    )]
+    pub pool_escrow: Account<'info, TokenAccount>,
+
+    /// Required unless the position pays nothing
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == owner.key()
+    )]
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Required when there is a fee to pay or dust to sweep
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == market.load_market()?.fee_claimer
+    )]
+    pub fee_recipient_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump = owner_profile.bump
+    )]
+    pub owner_profile: Account<'info, UserProfile>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Pay a settled position `payout` out of a pool's escrow, release its
+/// stake from the market and record the result on the owner's profile.
+/// Winnings pay the same fee as bets; refunds are free. Returns the amount
+/// paid to the owner and the fee. Shared by both kinds of pool.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pay_out_position<'info>(
+    market: &mut BettingMarket,
+    owner_profile: &mut UserProfile,
+    stake: u64,
+    payout: u64,
+    refunded: bool,
+    pool: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    pool_escrow: &Account<'info, TokenAccount>,
+    owner_token_account: Option<&Account<'info, TokenAccount>>,
+    fee_recipient_token_account: Option<&Account<'info, TokenAccount>>,
+    token_program: &Program<'info, Token>,
+) -> Result<(u64, u64)> {
+    let fee_amount = if refunded {
+        0
+    } else {
+        payout
+            .checked_mul(FEE_PERCENTAGE)
+            .unwrap()
+            .checked_div(FEE_DENOMINATOR)
+            .unwrap()
+    };
+    let payout_amount = payout.checked_sub(fee_amount).unwrap();
+
+    market.record_fees(Clock::get()?.unix_timestamp, fee_amount);
+    market.remove_open_interest(stake);
+    market.remove_open_bet()?;
+
+    if refunded {
+        owner_profile.record_push();
+    } else {
+        owner_profile.record_settlement(payout > 0, stake);
+        owner_profile.record_claim(payout_amount, fee_amount);
+    }
+
+    for (to, amount) in [
+        (fee_recipient_token_account, fee_amount),
+        (owner_token_account, payout_amount),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let to = to.ok_or(ErrorCode::TokenAccountRequired)?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: pool_escrow.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: pool.clone(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+
+    Ok((payout_amount, fee_amount))
+}
+
+/// Once a pool's last position is claimed, sweep the rounding dust left in
+/// its escrow to the fee claimer and close the escrow, returning its rent to
+/// `destination`. Shared by both kinds of pool.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn close_pool_escrow<'info>(
+    market: &mut BettingMarket,
+    pool: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    pool_escrow: &mut Account<'info, TokenAccount>,
+    fee_recipient_token_account: Option<&Account<'info, TokenAccount>>,
+    destination: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    pool_escrow.reload()?;
+    let dust = pool_escrow.amount;
+    if dust > 0 {
+        let fee_recipient_token_account =
+            fee_recipient_token_account.ok_or(ErrorCode::TokenAccountRequired)?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: pool_escrow.to_account_info(),
+                    to: fee_recipient_token_account.to_account_info(),
+                    authority: pool.clone(),
+                },
+                signer_seeds,
+            ),
+            dust,
+        )?;
+        market.record_fees(Clock::get()?.unix_timestamp, dust);
+    }
+
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: pool_escrow.to_account_info(),
+            destination,
+            authority: pool,
+        },
+        signer_seeds,
+    ))
+}
+
+pub fn claim_pool(ctx: Context<ClaimPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let position = &ctx.accounts.position;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    require!(pool.is_settled, ErrorCode::PoolNotSettled);
+    let last_position = pool.close_position();
+
+    let settlement_time = pool.settlement_time.to_le_bytes();
+    let price_threshold = pool.price_threshold.to_le_bytes();
+    let price_direction = pool.price_direction.seed();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"pool".as_ref(),
+        pool.market.as_ref(),
+        &settlement_time,
+        &price_threshold,
+        &price_direction,
+        &[pool.bump],
+    ]];
+
+    let (payout_amount, fee_amount) = pay_out_position(
+        &mut market,
+        &mut ctx.accounts.owner_profile,
+        position.amount,
+        pool.payout(position.side, position.amount),
+        pool.winning_side.is_none(),
+        pool.to_account_info(),
+        signer_seeds,
+        &ctx.accounts.pool_escrow,
+        ctx.accounts.owner_token_account.as_ref(),
+        ctx.accounts.fee_recipient_token_account.as_ref(),
+        &ctx.accounts.token_program,
+    )?;
+
+    if last_position {
+        close_pool_escrow(
+            &mut market,
+            pool.to_account_info(),
+            signer_seeds,
+            &mut ctx.accounts.pool_escrow,
+            ctx.accounts.fee_recipient_token_account.as_ref(),
+            ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    emit!(PoolClaimed {
+        pool: pool.key(),
+        position: position.key(),
+        owner: position.owner,
+        payout_amount,
+        fee_amount,
+    });
+
+    msg!(
+        "Pool position claimed by {}: {} USDC (with {} USDC fee) on {}",
+        position.owner,
+        payout_amount / 1_000_000,
+        fee_amount / 1_000_000,
+        market.get_token_name()
+    );
+
+    Ok(())
+}
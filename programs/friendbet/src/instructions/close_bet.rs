@@ -1,10 +1,20 @@
 use crate::errors::ErrorCode;
-use crate::state::{Bet, BettingMarket};
+use crate::state::{Bet, BettingMarket, MarketLoader};
 use crate::ADMIN;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use std::str::FromStr;
 
+#[event]
+pub struct BetClosed {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub better: Pubkey,
+    pub closer: Pubkey,
+    pub refund_recipient: Pubkey,
+    pub refund_amount: u64,
+}
+
 #[derive(Accounts)]
 pub struct CloseBet<'info> {
     /// CHECK: The account of the original bettor.
@@ -18,6 +28,7 @@ pub struct CloseBet<'info> {
         mut,
         seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
         bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration,
         constraint = bet.better == better.key() @ ErrorCode::InvalidBetter,
         constraint = !bet.is_matched @ ErrorCode::BetAlreadyMatched,
         close = better // Rent from bet account closure goes to the original better
@@ -26,11 +37,11 @@ pub struct CloseBet<'info> {
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
         constraint = market.key() == bet.market
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
     #[account(
         mut,
@@ -38,10 +49,11 @@ pub struct CloseBet<'info> {
     )]
     pub bet_escrow: Account<'info, TokenAccount>,
 
+    /// Receives the escrowed stake. Must belong to whoever funded the bet.
     #[account(mut,
-        constraint = &better_token_account.owner == &better.key()
+        constraint = !bet.is_funded || refund_token_account.owner == bet.refund_recipient() @ ErrorCode::InvalidFunder
     )]
-    pub better_token_account: Account<'info, TokenAccount>,
+    pub refund_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -61,7 +73,7 @@ pub fn close_bet(ctx: Context<CloseBet>) -> Result<()> {
     }
 
     let bet = &ctx.accounts.bet;
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
 
     // Create signing authority for the bet PDA
     let signer_seeds: &[&[&[u8]]] = &[&[
@@ -71,18 +83,20 @@ pub fn close_bet(ctx: Context<CloseBet>) -> Result<()> {
         &[bet.bump],
     ]];
 
-    // Return the funds to the better
+    let refund_amount = ctx.accounts.bet_escrow.amount;
+
+    // Return the funds to whoever funded the bet
     token::transfer(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.bet_escrow.to_account_info(),
-                to: ctx.accounts.better_token_account.to_account_info(),
+                to: ctx.accounts.refund_token_account.to_account_info(),
                 authority: ctx.accounts.bet.to_account_info(),
             },
             signer_seeds,
         ),
-        ctx.accounts.bet_escrow.amount,
+        refund_amount,
     )?;
 
     // Close the bet account
@@ -90,14 +104,24 @@ pub fn close_bet(ctx: Context<CloseBet>) -> Result<()> {
         .bet
         .close(ctx.accounts.better.to_account_info())?;
 
-    // Update market stats
-    market.total_volume = market.total_volume.checked_sub(bet.amount).unwrap();
+    // Update market stats (unmatched bets never counted towards volume)
+    market.remove_open_interest(refund_amount);
+    market.remove_open_bet()?;
+
+    emit!(BetClosed {
+        bet: bet.key(),
+        market: ctx.accounts.market.key(),
+        better: bet.better,
+        closer: ctx.accounts.closer.key(),
+        refund_recipient: bet.refund_recipient(),
+        refund_amount,
+    });
 
     msg!(
         "Bet closed by {}: {} USDC returned to {} from bet on {}. Bet account and token escrow account closed.",
         ctx.accounts.closer.key(), // Log who initiated the close
         bet.amount / 1_000_000,
-        ctx.accounts.better.key(), // Log who received the funds and rent
+        bet.refund_recipient(), // Log who received the funds
         market.get_token_name()
     );
 
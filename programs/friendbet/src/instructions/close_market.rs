@@ -0,0 +1,42 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, MarketRegistry, MarketStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.load_market()?.status() == MarketStatus::CloseOnly @ ErrorCode::MarketNotCloseOnly,
+        constraint = market.load_market()?.open_bets_untracked == 0 @ ErrorCode::OpenBetCountNotBackfilled,
+        constraint = market.load_market()?.open_bet_count == 0 @ ErrorCode::MarketHasOpenBets,
+        close = authority
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, MarketRegistry>,
+}
+
+pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+    let market = ctx.accounts.market.load_market()?;
+    ctx.accounts.registry.remove(&ctx.accounts.market.key())?;
+
+    msg!(
+        "Market {} closed: {} bets, {} USDC volume, {} USDC fees",
+        market.get_token_name(),
+        market.bet_count,
+        market.total_volume / 1_000_000,
+        market.total_fees_collected / 1_000_000
+    );
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use crate::state::{BettingMarket, MarketLoader, Offer};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct OfferClosed {
+    pub offer: Pubkey,
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub refund_amount: u64,
+    pub bets_taken: u64,
+}
+
+/// Withdraws the untaken remainder of an offer and closes it along with its
+/// vault. Bets already taken from the offer are unaffected.
+#[derive(Accounts)]
+pub struct CloseOffer<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offer", market.key().as_ref(), creator.key().as_ref(), &offer.offer_id.to_le_bytes()],
+        bump = offer.bump,
+        close = creator
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == offer.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        constraint = offer_vault.key() == offer.vault
+    )]
+    pub offer_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key()
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn close_offer(ctx: Context<CloseOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"offer".as_ref(),
+        offer.market.as_ref(),
+        offer.creator.as_ref(),
+        &offer.offer_id.to_le_bytes(),
+        &[offer.bump],
+    ]];
+
+    let refund_amount = ctx.accounts.offer_vault.amount;
+    if refund_amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.offer_vault.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: offer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            refund_amount,
+        )?;
+    }
+
+    // Return the vault's rent along with the offer's
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.offer_vault.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: offer.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    market.remove_open_interest(offer.remaining);
+    market.remove_open_bet()?;
+
+    emit!(OfferClosed {
+        offer: offer.key(),
+        market: offer.market,
+        creator: offer.creator,
+        refund_amount,
+        bets_taken: offer.bets_taken,
+    });
+
+    msg!(
+        "Offer closed by {}: {} USDC returned after {} bets taken on {}",
+        offer.creator,
+        refund_amount / 1_000_000,
+        offer.bets_taken,
+        market.get_token_name()
+    );
+
+    Ok(())
+}
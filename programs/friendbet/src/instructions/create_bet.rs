@@ -1,17 +1,33 @@
-use crate::errors::ErrorCode;
 use crate::state::PriceDirection;
-use crate::state::{Bet, BettingMarket};
+use crate::state::{Bet, BettingMarket, MarketLoader, UserBetIndex, UserProfile};
 use crate::USDC_MINT;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use std::str::FromStr;
 
+#[event]
+pub struct BetCreated {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub better: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub price_threshold: u64,
+    pub price_direction: PriceDirection,
+    pub settlement_time: i64,
+    pub is_funded: bool,
+    pub created_by_admin: bool,
+    pub bet_count: u64,
+    pub memo: String,
+}
+
 #[derive(Accounts)]
 #[instruction(
     bet_amount: u64,
     price_threshold: u64,
     price_direction: PriceDirection,
-    settlement_time: i64
+    settlement_time: i64,
+    memo: String
 )]
 pub struct CreateBet<'info> {
     #[account(mut)]
@@ -19,16 +35,16 @@ pub struct CreateBet<'info> {
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
     #[account(
         init,
         payer = better,
         space = Bet::LEN,
-        seeds = [b"bet", market.key().as_ref(), &(market.bet_count + 1).to_le_bytes()],
+        seeds = [b"bet", market.key().as_ref(), &(market.load_market()?.bet_count + 1).to_le_bytes()],
         bump
     )]
     pub bet: Account<'info, Bet>,
@@ -52,6 +68,22 @@ pub struct CreateBet<'info> {
     )]
     pub better_token_account: Account<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"profile", better.key().as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Account<'info, UserProfile>,
+
+    #[account(
+        init,
+        payer = better,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", better.key().as_ref(), &better_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub better_bet_index: Account<'info, UserBetIndex>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -63,22 +95,18 @@ pub fn create_bet(
     price_threshold: u64,
     price_direction: PriceDirection,
     settlement_time: i64,
+    memo: String,
 ) -> Result<()> {
+    ctx.accounts.market.load_market()?.require_active()?;
+
     let current_time = Clock::get()?.unix_timestamp;
 
-    require!(
-        settlement_time > current_time,
-        ErrorCode::InvalidSettlementTime
-    );
-    require!(
-        settlement_time - current_time >= 3600,
-        ErrorCode::SettlementTimeTooClose
-    );
-    require!(
-        bet_amount > 1_000_000, // 1 USDC
-        ErrorCode::InvalidBetAmount
-    );
-    require!(price_threshold > 0, ErrorCode::InvalidPriceThreshold);
+    ctx.accounts
+        .market
+        .load_market()?
+        .check_settlement_time(settlement_time, current_time)?;
+    ctx.accounts.market.load_market()?.check_stake(bet_amount)?;
+    price_direction.validate(ctx.accounts.market.key(), price_threshold)?;
 
     // Transfer USDC from better to bet escrow
     let cpi_accounts = Transfer {
@@ -91,7 +119,7 @@ pub fn create_bet(
     token::transfer(cpi_ctx, bet_amount)?;
 
     // Increment bet count first so we store the accurate count in the bet
-    let bet_count = ctx.accounts.market.bet_count + 1;
+    let bet_count = ctx.accounts.market.load_market()?.bet_count + 1;
 
     // Update bet account
     let bet = &mut ctx.accounts.bet;
@@ -101,6 +129,11 @@ pub fn create_bet(
     bet.price_threshold = price_threshold;
     bet.price_direction = price_direction;
     bet.settlement_time = settlement_time;
+    bet.settles_on_round = ctx
+        .accounts
+        .market
+        .load_market()?
+        .is_settlement_slot(settlement_time);
     bet.is_matched = false;
     bet.is_settled = false;
     bet.is_funded = true; // Funded immediately since user provided the funds
@@ -109,24 +142,49 @@ pub fn create_bet(
     bet.matcher = None;
     bet.escrow = ctx.accounts.bet_escrow.key();
     bet.bet_count = bet_count; // Store the bet count in the bet
+    bet.funder = ctx.accounts.better.key(); // Self-funded
+    bet.gift_winnings = false;
+    bet.set_memo(&memo)?;
+    bet.version = Bet::VERSION;
     bet.bump = ctx.bumps.bet;
 
     // Update market stats
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
     market.bet_count = bet_count;
-    market.total_volume = market.total_volume.checked_add(bet_amount).unwrap();
+    market.add_open_bet();
+    market.add_open_interest(bet_amount)?;
+
+    let better_profile = &mut ctx.accounts.better_profile;
+    better_profile.bets_created = better_profile.bets_created.checked_add(1).unwrap();
+
+    // Record the bet in the better's bet index
+    let better_bet_index = &mut ctx.accounts.better_bet_index;
+    better_bet_index.owner = better_profile.owner;
+    better_bet_index.index = better_profile.next_bet_index();
+    better_bet_index.bet = bet.key();
+    better_bet_index.bump = ctx.bumps.better_bet_index;
+
+    emit!(BetCreated {
+        bet: bet.key(),
+        market: ctx.accounts.market.key(),
+        better: bet.better,
+        funder: bet.funder,
+        amount: bet.amount,
+        price_threshold: bet.price_threshold,
+        price_direction: bet.price_direction,
+        settlement_time: bet.settlement_time,
+        is_funded: bet.is_funded,
+        created_by_admin: bet.created_by_admin,
+        bet_count: bet.bet_count,
+        memo: bet.get_memo(),
+    });
 
     msg!(
-        "Bet created by {}: {} USDC that {} will be trading {} {}",
+        "Bet created by {}: {} USDC that {} will be trading {}",
         bet.better,
         bet.amount / 1_000_000,
         market.get_token_name(),
-        if price_direction == PriceDirection::Above {
-            "above"
-        } else {
-            "below"
-        },
-        price_threshold
+        price_direction.describe(price_threshold)
     );
 
     Ok(())
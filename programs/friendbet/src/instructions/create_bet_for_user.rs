@@ -1,6 +1,6 @@
-use crate::errors::ErrorCode;
+use crate::instructions::create_bet::BetCreated;
 use crate::state::PriceDirection;
-use crate::state::{Bet, BettingMarket};
+use crate::state::{Bet, BettingMarket, MarketLoader, UserBetIndex, UserProfile};
 use crate::USDC_MINT;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
@@ -13,7 +13,9 @@ use std::str::FromStr;
     price_direction: PriceDirection,
     settlement_time: i64,
     better_pubkey: Pubkey,
-    fund_immediately: bool
+    fund_immediately: bool,
+    gift_winnings: bool,
+    memo: String
 )]
 pub struct CreateBetForUser<'info> {
     #[account(mut)]
@@ -23,16 +25,16 @@ pub struct CreateBetForUser<'info> {
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
     #[account(
         init,
         payer = admin,
         space = Bet::LEN,
-        seeds = [b"bet", market.key().as_ref(), &(market.bet_count + 1).to_le_bytes()],
+        seeds = [b"bet", market.key().as_ref(), &(market.load_market()?.bet_count + 1).to_le_bytes()],
         bump
     )]
     pub bet: Account<'info, Bet>,
@@ -53,11 +55,28 @@ pub struct CreateBetForUser<'info> {
     #[account(mut)]
     pub funder_token_account: Option<UncheckedAccount<'info>>,
 
+    #[account(
+        mut,
+        seeds = [b"profile", better_pubkey.as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Account<'info, UserProfile>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", better_pubkey.as_ref(), &better_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub better_bet_index: Account<'info, UserBetIndex>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_bet_for_user(
     ctx: Context<CreateBetForUser>,
     bet_amount: u64,
@@ -66,22 +85,19 @@ pub fn create_bet_for_user(
     settlement_time: i64,
     better_pubkey: Pubkey,
     fund_immediately: bool,
+    gift_winnings: bool,
+    memo: String,
 ) -> Result<()> {
+    ctx.accounts.market.load_market()?.require_active()?;
+
     let current_time = Clock::get()?.unix_timestamp;
 
-    require!(
-        settlement_time > current_time,
-        ErrorCode::InvalidSettlementTime
-    );
-    require!(
-        settlement_time - current_time >= 3600,
-        ErrorCode::SettlementTimeTooClose
-    );
-    require!(
-        bet_amount > 1_000_000, // 1 USDC
-        ErrorCode::InvalidBetAmount
-    );
-    require!(price_threshold > 0, ErrorCode::InvalidPriceThreshold);
+    ctx.accounts
+        .market
+        .load_market()?
+        .check_settlement_time(settlement_time, current_time)?;
+    ctx.accounts.market.load_market()?.check_stake(bet_amount)?;
+    price_direction.validate(ctx.accounts.market.key(), price_threshold)?;
 
     // If funding immediately, validate the funder token account
     if fund_immediately {
@@ -101,7 +117,7 @@ pub fn create_bet_for_user(
     }
 
     // Increment bet count first so we store the accurate count in the bet
-    let bet_count = ctx.accounts.market.bet_count + 1;
+    let bet_count = ctx.accounts.market.load_market()?.bet_count + 1;
 
     // Update bet account
     let bet = &mut ctx.accounts.bet;
@@ -111,6 +127,11 @@ pub fn create_bet_for_user(
     bet.price_threshold = price_threshold;
     bet.price_direction = price_direction;
     bet.settlement_time = settlement_time;
+    bet.settles_on_round = ctx
+        .accounts
+        .market
+        .load_market()?
+        .is_settlement_slot(settlement_time);
     bet.is_matched = false;
     bet.is_settled = false;
     bet.is_funded = fund_immediately; // Set funded status based on whether we funded immediately
@@ -119,33 +140,63 @@ pub fn create_bet_for_user(
     bet.matcher = None;
     bet.escrow = ctx.accounts.bet_escrow.key();
     bet.bet_count = bet_count; // Store the bet count in the bet
+
+    // Record who put up the stake so refunds go back to them rather than the better
+    bet.funder = if fund_immediately {
+        ctx.accounts.funder.key()
+    } else {
+        Pubkey::default()
+    };
+    bet.gift_winnings = gift_winnings;
+    bet.set_memo(&memo)?;
+    bet.version = Bet::VERSION;
     bet.bump = ctx.bumps.bet;
 
     // Update market stats
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
     market.bet_count = bet_count;
+    market.add_open_bet();
 
-    // Only add to total_volume if funded immediately
     if fund_immediately {
-        market.total_volume = market.total_volume.checked_add(bet_amount).unwrap();
+        market.add_open_interest(bet_amount)?;
     }
 
+    let better_profile = &mut ctx.accounts.better_profile;
+    better_profile.bets_created = better_profile.bets_created.checked_add(1).unwrap();
+
+    // Record the bet in the better's bet index
+    let better_bet_index = &mut ctx.accounts.better_bet_index;
+    better_bet_index.owner = better_profile.owner;
+    better_bet_index.index = better_profile.next_bet_index();
+    better_bet_index.bet = bet.key();
+    better_bet_index.bump = ctx.bumps.better_bet_index;
+
+    emit!(BetCreated {
+        bet: bet.key(),
+        market: ctx.accounts.market.key(),
+        better: bet.better,
+        funder: bet.funder,
+        amount: bet.amount,
+        price_threshold: bet.price_threshold,
+        price_direction: bet.price_direction,
+        settlement_time: bet.settlement_time,
+        is_funded: bet.is_funded,
+        created_by_admin: bet.created_by_admin,
+        bet_count: bet.bet_count,
+        memo: bet.get_memo(),
+    });
+
     let funding_status = if fund_immediately {
         "FUNDED"
     } else {
         "UNFUNDED"
     };
     msg!(
-        "Bet created by admin for {}: {} USDC that {} will be trading {} {} ({})",
+        "Bet created by admin for {}: {} USDC that {} will be trading {} ({})",
         bet.better,
         bet.amount / 1_000_000,
         market.get_token_name(),
-        if price_direction == PriceDirection::Above {
-            "above"
-        } else {
-            "below"
-        },
-        price_threshold,
+        price_direction.describe(price_threshold),
         funding_status
     );
 
@@ -0,0 +1,97 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, BucketPool, MarketLoader};
+use crate::USDC_MINT;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+use std::str::FromStr;
+
+#[event]
+pub struct BucketPoolCreated {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub settlement_time: i64,
+    pub boundaries: Vec<u64>,
+}
+
+/// Opens a multi-outcome pool on where the price settles for a slot. Anyone
+/// can create it; there is one pool per market, slot and set of boundaries.
+#[derive(Accounts)]
+#[instruction(settlement_time: i64, boundaries: Vec<u64>)]
+pub struct CreateBucketPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = BucketPool::LEN,
+        seeds = [
+            b"bucket_pool",
+            market.key().as_ref(),
+            &settlement_time.to_le_bytes(),
+            &BucketPool::boundaries_seed(&boundaries)
+        ],
+        bump
+    )]
+    pub pool: Account<'info, BucketPool>,
+
+    #[account(
+        constraint = usdc_mint.key() == Pubkey::from_str(USDC_MINT).unwrap()
+    )]
+    pub usdc_mint: Account<'info, token::Mint>,
+
+    #[account(
+        token::mint = usdc_mint,
+        token::authority = pool,
+    )]
+    pub pool_escrow: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_bucket_pool(
+    ctx: Context<CreateBucketPool>,
+    settlement_time: i64,
+    boundaries: Vec<u64>,
+) -> Result<()> {
+    let market = ctx.accounts.market.load_market()?;
+    market.require_active()?;
+
+    // Bucket pools settle on the slot's recorded price, so they need a scheduled slot
+    require!(
+        market.is_settlement_slot(settlement_time),
+        ErrorCode::SettlementTimeNotOnSlot
+    );
+    market.check_settlement_time(settlement_time, Clock::get()?.unix_timestamp)?;
+    BucketPool::validate_boundaries(&boundaries)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.market = ctx.accounts.market.key();
+    pool.settlement_time = settlement_time;
+    pool.totals = vec![0; boundaries.len() + 1];
+    pool.boundaries = boundaries;
+    pool.escrow = ctx.accounts.pool_escrow.key();
+    pool.bump = ctx.bumps.pool;
+
+    emit!(BucketPoolCreated {
+        pool: pool.key(),
+        market: pool.market,
+        settlement_time,
+        boundaries: pool.boundaries.clone(),
+    });
+
+    msg!(
+        "Bucket pool created on {} at {} with {} buckets",
+        market.get_token_name(),
+        settlement_time,
+        pool.totals.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use crate::errors::ErrorCode;
+use crate::state::{encode_memo, BettingMarket, MarketLoader, Offer, PriceDirection};
+use crate::USDC_MINT;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use std::str::FromStr;
+
+#[event]
+pub struct OfferCreated {
+    pub offer: Pubkey,
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub offer_id: u64,
+    pub capacity: u64,
+    pub price_threshold: u64,
+    pub price_direction: PriceDirection,
+    pub settlement_time: i64,
+    pub memo: String,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct CreateOffer<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = Offer::LEN,
+        seeds = [b"offer", market.key().as_ref(), creator.key().as_ref(), &offer_id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        constraint = usdc_mint.key() == Pubkey::from_str(USDC_MINT).unwrap()
+    )]
+    pub usdc_mint: Account<'info, token::Mint>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = offer,
+    )]
+    pub offer_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == usdc_mint.key(),
+        constraint = creator_token_account.owner == creator.key()
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_offer(
+    ctx: Context<CreateOffer>,
+    offer_id: u64,
+    capacity: u64,
+    price_threshold: u64,
+    price_direction: PriceDirection,
+    settlement_time: i64,
+    memo: String,
+) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    market.require_active()?;
+    market.check_settlement_time(settlement_time, Clock::get()?.unix_timestamp)?;
+    price_direction.validate(ctx.accounts.market.key(), price_threshold)?;
+
+    // Takes are matched without a fresh price, so the condition must be fixed up front
+    require!(
+        !price_direction.is_relative(),
+        ErrorCode::UnsupportedOfferDirection
+    );
+    require!(capacity > 0, ErrorCode::InvalidOfferCapacity);
+    market.check_stake(capacity)?;
+
+    // Transfer the whole capacity from the creator to the offer vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.creator_token_account.to_account_info(),
+        to: ctx.accounts.offer_vault.to_account_info(),
+        authority: ctx.accounts.creator.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, capacity)?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.market = ctx.accounts.market.key();
+    offer.creator = ctx.accounts.creator.key();
+    offer.offer_id = offer_id;
+    offer.price_threshold = price_threshold;
+    offer.price_direction = price_direction;
+    offer.settlement_time = settlement_time;
+    offer.capacity = capacity;
+    offer.remaining = capacity;
+    offer.vault = ctx.accounts.offer_vault.key();
+    offer.memo = encode_memo(&memo)?;
+    offer.bump = ctx.bumps.offer;
+
+    // An open offer holds funds on the market, so it can't be closed under it,
+    // and its whole capacity counts as open interest until taken or withdrawn
+    market.add_open_interest(capacity)?;
+    market.add_open_bet();
+
+    emit!(OfferCreated {
+        offer: offer.key(),
+        market: offer.market,
+        creator: offer.creator,
+        offer_id,
+        capacity,
+        price_threshold,
+        price_direction,
+        settlement_time,
+        memo,
+    });
+
+    msg!(
+        "Offer created by {}: up to {} USDC that {} will be trading {}",
+        offer.creator,
+        capacity / 1_000_000,
+        market.get_token_name(),
+        price_direction.describe(price_threshold)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, Parlay, ParlayLeg};
+use crate::USDC_MINT;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use std::str::FromStr;
+
+#[event]
+pub struct ParlayCreated {
+    pub parlay: Pubkey,
+    pub better: Pubkey,
+    pub parlay_id: u64,
+    pub amount: u64,
+    pub matcher_amount: u64,
+    pub legs: Vec<ParlayLeg>,
+}
+
+/// Creates a parlay over `legs`. The legs' markets are passed as writable
+/// remaining accounts, in leg order.
+#[derive(Accounts)]
+#[instruction(parlay_id: u64)]
+pub struct CreateParlay<'info> {
+    #[account(mut)]
+    pub better: Signer<'info>,
+
+    #[account(
+        init,
+        payer = better,
+        space = Parlay::LEN,
+        seeds = [b"parlay", better.key().as_ref(), &parlay_id.to_le_bytes()],
+        bump
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    #[account(
+        constraint = usdc_mint.key() == Pubkey::from_str(USDC_MINT).unwrap()
+    )]
+    pub usdc_mint: Account<'info, token::Mint>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = parlay,
+    )]
+    pub parlay_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = better_token_account.mint == usdc_mint.key(),
+        constraint = better_token_account.owner == better.key()
+    )]
+    pub better_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Load the markets passed for each leg of a parlay, in leg order
+pub(crate) fn leg_markets<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    legs: &[ParlayLeg],
+) -> Result<Vec<AccountLoader<'info, BettingMarket>>> {
+    require!(
+        remaining_accounts.len() == legs.len(),
+        ErrorCode::InvalidParlayLeg
+    );
+    legs.iter()
+        .zip(remaining_accounts)
+        .map(|(leg, account)| {
+            require_keys_eq!(account.key(), leg.market, ErrorCode::InvalidParlayLeg);
+            AccountLoader::try_from(account)
+        })
+        .collect()
+}
+
+pub fn create_parlay<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateParlay<'info>>,
+    parlay_id: u64,
+    amount: u64,
+    matcher_amount: u64,
+    legs: Vec<ParlayLeg>,
+) -> Result<()> {
+    Parlay::validate_legs(&legs)?;
+
+    // Each leg must be a bet its market would accept on its own. The parlay
+    // counts as an open bet, and its escrow as open interest, on every leg's
+    // market until that leg is priced.
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut legs_on_round = Vec::with_capacity(legs.len());
+    for (leg, market) in legs.iter().zip(leg_markets(ctx.remaining_accounts, &legs)?) {
+        let mut market = market.load_market_mut()?;
+        market.require_active()?;
+        market.check_settlement_time(leg.settlement_time, current_time)?;
+        market.check_stake(amount)?;
+        market.check_stake(matcher_amount)?;
+        market.add_open_interest(amount)?;
+        market.add_open_bet();
+        legs_on_round.push(market.is_settlement_slot(leg.settlement_time));
+    }
+
+    // Transfer USDC from better to parlay escrow
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.better_token_account.to_account_info(),
+        to: ctx.accounts.parlay_escrow.to_account_info(),
+        authority: ctx.accounts.better.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let parlay = &mut ctx.accounts.parlay;
+    parlay.better = ctx.accounts.better.key();
+    parlay.parlay_id = parlay_id;
+    parlay.amount = amount;
+    parlay.matcher_amount = matcher_amount;
+    parlay.leg_prices = vec![None; legs.len()];
+    parlay.legs_on_round = legs_on_round;
+    parlay.legs = legs;
+    parlay.escrow = ctx.accounts.parlay_escrow.key();
+    parlay.bump = ctx.bumps.parlay;
+
+    emit!(ParlayCreated {
+        parlay: parlay.key(),
+        better: parlay.better,
+        parlay_id,
+        amount,
+        matcher_amount,
+        legs: parlay.legs.clone(),
+    });
+
+    msg!(
+        "Parlay created by {}: {} USDC against {} USDC over {} legs",
+        parlay.better,
+        amount / 1_000_000,
+        matcher_amount / 1_000_000,
+        parlay.legs.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, Pool, PriceDirection};
+use crate::USDC_MINT;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+use std::str::FromStr;
+
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub settlement_time: i64,
+    pub price_threshold: u64,
+    pub price_direction: PriceDirection,
+}
+
+/// Opens a parimutuel pool for one condition on a settlement slot. Anyone can
+/// create it; there is one pool per market, slot, threshold and direction.
+#[derive(Accounts)]
+#[instruction(settlement_time: i64, price_threshold: u64, price_direction: PriceDirection)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Pool::LEN,
+        seeds = [
+            b"pool",
+            market.key().as_ref(),
+            &settlement_time.to_le_bytes(),
+            &price_threshold.to_le_bytes(),
+            &price_direction.seed()
+        ],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        constraint = usdc_mint.key() == Pubkey::from_str(USDC_MINT).unwrap()
+    )]
+    pub usdc_mint: Account<'info, token::Mint>,
+
+    #[account(
+        token::mint = usdc_mint,
+        token::authority = pool,
+    )]
+    pub pool_escrow: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_pool(
+    ctx: Context<CreatePool>,
+    settlement_time: i64,
+    price_threshold: u64,
+    price_direction: PriceDirection,
+) -> Result<()> {
+    let market = ctx.accounts.market.load_market()?;
+    market.require_active()?;
+
+    // Pools settle on the slot's recorded price, so they need a scheduled slot
+    require!(
+        market.is_settlement_slot(settlement_time),
+        ErrorCode::SettlementTimeNotOnSlot
+    );
+    market.check_settlement_time(settlement_time, Clock::get()?.unix_timestamp)?;
+
+    require!(
+        price_direction.is_absolute(),
+        ErrorCode::UnsupportedPoolDirection
+    );
+    price_direction.validate(ctx.accounts.market.key(), price_threshold)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.market = ctx.accounts.market.key();
+    pool.settlement_time = settlement_time;
+    pool.price_threshold = price_threshold;
+    pool.price_direction = price_direction;
+    pool.escrow = ctx.accounts.pool_escrow.key();
+    pool.bump = ctx.bumps.pool;
+
+    emit!(PoolCreated {
+        pool: pool.key(),
+        market: pool.market,
+        settlement_time,
+        price_threshold,
+        price_direction,
+    });
+
+    msg!(
+        "Pool created on {}: {} at {}",
+        market.get_token_name(),
+        price_direction.describe(price_threshold),
+        settlement_time
+    );
+
+    Ok(())
+}
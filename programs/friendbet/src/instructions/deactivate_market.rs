@@ -0,0 +1,45 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, MarketRegistry, MarketStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DeactivateMarket<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, MarketRegistry>,
+}
+
+/// Moves a market to close-only: no new bets or matches, but existing bets can
+/// still be settled, claimed and closed.
+pub fn deactivate_market(ctx: Context<DeactivateMarket>) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    require!(
+        market.status() == MarketStatus::Active,
+        ErrorCode::MarketNotActive
+    );
+    market.set_status(MarketStatus::CloseOnly);
+
+    ctx.accounts
+        .registry
+        .set_status(&ctx.accounts.market.key(), MarketStatus::CloseOnly)?;
+
+    msg!(
+        "Market {} deactivated with {} open bets",
+        market.get_token_name(),
+        market.open_bet_count
+    );
+
+    Ok(())
+}
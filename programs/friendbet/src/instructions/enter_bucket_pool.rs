@@ -0,0 +1,118 @@
+use crate::errors::ErrorCode;
+use crate::instructions::enter_pool::deposit_into_pool;
+use crate::state::{BettingMarket, BucketPool, BucketPosition, MarketLoader};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[event]
+pub struct BucketPoolEntered {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub bucket: u8,
+    pub amount: u64,
+    pub bucket_total: u64,
+}
+
+#[derive(Accounts)]
+pub struct EnterBucketPool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bucket_pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &BucketPool::boundaries_seed(&pool.boundaries)
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BucketPool>,
+
+    #[account(
+        mut,
+        constraint = pool_escrow.key() == pool.escrow
+    )]
+    pub pool_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == pool_escrow.mint,
+        constraint = owner_token_account.owner == owner.key()
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = BucketPosition::LEN,
+        seeds = [b"bucket_position", pool.key().as_ref(), &(pool.position_count + 1).to_le_bytes()],
+        bump
+    )]
+    pub position: Account<'info, BucketPosition>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn enter_bucket_pool(ctx: Context<EnterBucketPool>, bucket: u8, amount: u64) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.is_settled, ErrorCode::PoolAlreadySettled);
+    require!(
+        (bucket as usize) < pool.totals.len(),
+        ErrorCode::InvalidBucket
+    );
+
+    deposit_into_pool(
+        &mut market,
+        pool.settlement_time,
+        amount,
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.pool_escrow,
+        &ctx.accounts.token_program,
+    )?;
+
+    let bucket_total = &mut pool.totals[bucket as usize];
+    *bucket_total = bucket_total.checked_add(amount).unwrap();
+    let bucket_total = *bucket_total;
+    pool.position_count = pool.position_count.checked_add(1).unwrap();
+
+    let position = &mut ctx.accounts.position;
+    position.pool = pool.key();
+    position.owner = ctx.accounts.owner.key();
+    position.bucket = bucket;
+    position.amount = amount;
+    position.position_index = pool.position_count;
+    position.bump = ctx.bumps.position;
+
+    emit!(BucketPoolEntered {
+        pool: pool.key(),
+        position: position.key(),
+        owner: position.owner,
+        bucket,
+        amount,
+        bucket_total,
+    });
+
+    msg!(
+        "{} entered bucket {} of pool on {} with {} USDC",
+        position.owner,
+        bucket,
+        market.get_token_name(),
+        amount / 1_000_000
+    );
+
+    Ok(())
+}
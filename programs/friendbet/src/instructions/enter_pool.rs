@@ -0,0 +1,148 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, Pool, PoolPosition, PoolSide};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct PoolEntered {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub side: PoolSide,
+    pub amount: u64,
+    pub yes_total: u64,
+    pub no_total: u64,
+}
+
+#[derive(Accounts)]
+pub struct EnterPool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &pool.price_threshold.to_le_bytes(),
+            &pool.price_direction.seed()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        constraint = pool_escrow.key() == pool.escrow
+    )]
+    pub pool_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == pool_escrow.mint,
+        constraint = owner_token_account.owner == owner.key()
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = PoolPosition::LEN,
+        seeds = [b"pool_position", pool.key().as_ref(), &(pool.position_count + 1).to_le_bytes()],
+        bump
+    )]
+    pub position: Account<'info, PoolPosition>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move a deposit from its owner into a pool's escrow before the pool's slot
+/// and count it on the market. Shared by both kinds of pool.
+pub(crate) fn deposit_into_pool<'info>(
+    market: &mut BettingMarket,
+    settlement_time: i64,
+    amount: u64,
+    owner: &Signer<'info>,
+    owner_token_account: &Account<'info, TokenAccount>,
+    pool_escrow: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    market.require_active()?;
+    market.check_stake(amount)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(current_time < settlement_time, ErrorCode::BetExpired);
+
+    let cpi_accounts = Transfer {
+        from: owner_token_account.to_account_info(),
+        to: pool_escrow.to_account_info(),
+        authority: owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    // Positions count as open bets so the market can't be closed under them
+    market.record_volume(current_time, amount);
+    market.add_open_interest(amount)?;
+    market.add_open_bet();
+    Ok(())
+}
+
+pub fn enter_pool(ctx: Context<EnterPool>, side: PoolSide, amount: u64) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.is_settled, ErrorCode::PoolAlreadySettled);
+
+    deposit_into_pool(
+        &mut market,
+        pool.settlement_time,
+        amount,
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.pool_escrow,
+        &ctx.accounts.token_program,
+    )?;
+
+    match side {
+        PoolSide::Yes => pool.yes_total = pool.yes_total.checked_add(amount).unwrap(),
+        PoolSide::No => pool.no_total = pool.no_total.checked_add(amount).unwrap(),
+    }
+    pool.position_count = pool.position_count.checked_add(1).unwrap();
+
+    let position = &mut ctx.accounts.position;
+    position.pool = pool.key();
+    position.owner = ctx.accounts.owner.key();
+    position.side = side;
+    position.amount = amount;
+    position.position_index = pool.position_count;
+    position.bump = ctx.bumps.position;
+
+    emit!(PoolEntered {
+        pool: pool.key(),
+        position: position.key(),
+        owner: position.owner,
+        side,
+        amount,
+        yes_total: pool.yes_total,
+        no_total: pool.no_total,
+    });
+
+    msg!(
+        "{} entered pool on {} with {} USDC",
+        position.owner,
+        market.get_token_name(),
+        amount / 1_000_000
+    );
+
+    Ok(())
+}
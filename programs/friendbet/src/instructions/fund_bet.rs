@@ -1,10 +1,19 @@
 use crate::errors::ErrorCode;
-use crate::state::{Bet, BettingMarket};
+use crate::state::{Bet, BettingMarket, MarketLoader};
 use crate::USDC_MINT;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use std::str::FromStr;
 
+#[event]
+pub struct BetFunded {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub gift_winnings: bool,
+}
+
 #[derive(Accounts)]
 pub struct FundBet<'info> {
     #[account(mut)]
@@ -12,15 +21,17 @@ pub struct FundBet<'info> {
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == bet.market @ ErrorCode::InvalidMarket
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
     #[account(
         mut,
         seeds = [b"bet", bet.market.as_ref(), &bet.bet_count.to_le_bytes()],
         bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration,
         constraint = !bet.is_funded @ ErrorCode::BetAlreadyFunded,
         constraint = !bet.is_matched @ ErrorCode::BetAlreadyMatched,
         constraint = !bet.is_settled @ ErrorCode::BetAlreadySettled,
@@ -48,14 +59,17 @@ pub struct FundBet<'info> {
     pub token_program: Program<'info, Token>,
 }
 
-pub fn fund_bet(ctx: Context<FundBet>) -> Result<()> {
+pub fn fund_bet(ctx: Context<FundBet>, gift_winnings: bool) -> Result<()> {
     let bet = &mut ctx.accounts.bet;
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
 
     // Check if settlement time hasn't passed
     let current_time = Clock::get()?.unix_timestamp;
     require!(current_time < bet.settlement_time, ErrorCode::BetExpired);
 
+    // Limits may have changed since the bet was created
+    market.check_stake(bet.amount)?;
+
     // Transfer USDC from funder to bet escrow
     let cpi_accounts = Transfer {
         from: ctx.accounts.funder_token_account.to_account_info(),
@@ -66,11 +80,21 @@ pub fn fund_bet(ctx: Context<FundBet>) -> Result<()> {
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     token::transfer(cpi_ctx, bet.amount)?;
 
-    // Mark bet as funded
+    // Mark bet as funded and remember the sponsor for refunds
     bet.is_funded = true;
+    bet.funder = ctx.accounts.funder.key();
+    bet.gift_winnings = gift_winnings;
+
+    // Update market stats; volume is only counted once the bet is matched
+    market.add_open_interest(bet.amount)?;
 
-    // Update market stats (add to total volume now that it's funded)
-    market.total_volume = market.total_volume.checked_add(bet.amount).unwrap();
+    emit!(BetFunded {
+        bet: bet.key(),
+        market: ctx.accounts.market.key(),
+        funder: bet.funder,
+        amount: bet.amount,
+        gift_winnings: bet.gift_winnings,
+    });
 
     msg!(
         "Bet {} funded by {} with {} USDC",
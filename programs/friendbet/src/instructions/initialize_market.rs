@@ -1,9 +1,23 @@
-use crate::state::BettingMarket;
+use crate::state::{
+    BettingMarket, MarketRegistry, MarketStatus, DEFAULT_MAX_DURATION, DEFAULT_MIN_DURATION,
+    MIN_STAKE,
+};
 use anchor_lang::prelude::*;
 use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
 
+#[event]
+pub struct MarketInitialized {
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub fee_claimer: Pubkey,
+    pub token_name: String,
+    pub feed_id: [u8; 32],
+    pub oracle_account: Pubkey,
+    pub market_index: u16,
+}
+
 #[derive(Accounts)]
-#[instruction(token_name: String, fee_claimer: Pubkey, feed_id_hex: String)]
+#[instruction(token_name: String, fee_claimer: Pubkey, feed_id_hex: String, market_index: u16)]
 pub struct InitializeMarket<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -12,10 +26,17 @@ pub struct InitializeMarket<'info> {
         init,
         payer = authority,
         space = BettingMarket::LEN,
-        seeds = [b"market", &get_feed_id_from_hex(&feed_id_hex).unwrap()[..8]],
+        seeds = [b"market", &get_feed_id_from_hex(&feed_id_hex).unwrap()[..], &market_index.to_le_bytes()],
         bump
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, MarketRegistry>,
 
     /// Check: The Pyth price update account
     #[account()]
@@ -29,21 +50,38 @@ pub fn initialize_market(
     token_name: String,
     fee_claimer: Pubkey,
     feed_id_hex: String,
+    market_index: u16,
 ) -> Result<()> {
     let feed_id_bytes = get_feed_id_from_hex(&feed_id_hex)?;
 
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_init()?;
     market.authority = ctx.accounts.authority.key();
     market.fee_claimer = fee_claimer;
     market.set_token_name(&token_name);
     market.feed_id = feed_id_bytes;
     market.oracle_account = ctx.accounts.price_update.key();
-    market.bet_count = 0;
-    market.total_volume = 0;
-    market.total_matched_count = 0;
-    market.total_settled_count = 0;
-    market.total_fees_collected = 0;
+    market.min_stake = MIN_STAKE;
+    market.min_duration = DEFAULT_MIN_DURATION as u32;
+    market.max_duration = DEFAULT_MAX_DURATION as u32;
+    market.set_market_index(Some(market_index));
+    market.set_status(MarketStatus::Active);
+    market.version = BettingMarket::VERSION;
     market.bump = ctx.bumps.market;
 
+    // List the market so clients can discover it
+    ctx.accounts
+        .registry
+        .register(ctx.accounts.market.key(), &market)?;
+
+    emit!(MarketInitialized {
+        market: ctx.accounts.market.key(),
+        authority: market.authority,
+        fee_claimer: market.fee_claimer,
+        token_name: market.get_token_name(),
+        feed_id: market.feed_id,
+        oracle_account: market.oracle_account,
+        market_index,
+    });
+
     Ok(())
 }
@@ -0,0 +1,56 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, FilterMode, MarketLoader, MatchFilter, MAX_FILTER_WALLETS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeMatchFilter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MatchFilter::LEN,
+        seeds = [b"match_filter", market.key().as_ref()],
+        bump
+    )]
+    pub match_filter: Account<'info, MatchFilter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_match_filter(
+    ctx: Context<InitializeMatchFilter>,
+    mode: FilterMode,
+    wallets: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        wallets.len() <= MAX_FILTER_WALLETS,
+        ErrorCode::TooManyFilterWallets
+    );
+
+    let match_filter = &mut ctx.accounts.match_filter;
+    match_filter.market = ctx.accounts.market.key();
+    match_filter.mode = mode;
+    match_filter.wallets = wallets;
+    match_filter.bump = ctx.bumps.match_filter;
+
+    // From now on match_bet must be given this filter
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    market.match_filter = match_filter.key();
+
+    msg!(
+        "Match filter initialized for {} with {} wallets",
+        market.get_token_name(),
+        match_filter.wallets.len()
+    );
+
+    Ok(())
+}
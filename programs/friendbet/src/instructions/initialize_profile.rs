@@ -0,0 +1,31 @@
+use crate::state::UserProfile;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitializeProfile<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = UserProfile::LEN,
+        seeds = [b"profile", owner.as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can pay for a profile, so admins can set one up for users they create bets for
+pub fn initialize_profile(ctx: Context<InitializeProfile>, owner: Pubkey) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    profile.owner = owner;
+    profile.bump = ctx.bumps.profile;
+
+    msg!("Profile initialized for {}", owner);
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::state::MarketRegistry;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MarketRegistry::LEN,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, MarketRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_registry(ctx: Context<InitializeRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.authority = ctx.accounts.authority.key();
+    registry.markets = Vec::new();
+    registry.bump = ctx.bumps.registry;
+
+    msg!("Market registry initialized");
+
+    Ok(())
+}
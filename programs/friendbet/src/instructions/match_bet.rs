@@ -1,8 +1,26 @@
 use crate::errors::ErrorCode;
-use crate::state::Bet;
+use crate::instructions::settle_bet::{
+    load_other_market, market_price, normalize_price, other_market_price,
+};
 use crate::state::BettingMarket;
+use crate::state::MatchFilter;
+use crate::state::UserBetIndex;
+use crate::state::UserProfile;
+use crate::state::{Bet, MarketLoader};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[event]
+pub struct BetMatched {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub better: Pubkey,
+    pub matcher: Pubkey,
+    pub amount: u64,
+    pub reference_price: u64,
+    pub other_reference_price: u64,
+}
 
 #[derive(Accounts)]
 pub struct MatchBet<'info> {
@@ -12,17 +30,18 @@ pub struct MatchBet<'info> {
     #[account(
         mut,
         seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
-        bump = bet.bump
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration
     )]
     pub bet: Account<'info, Bet>,
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
         constraint = market.key() == bet.market
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
     #[account(
         mut,
@@ -33,12 +52,74 @@ pub struct MatchBet<'info> {
     #[account(mut)]
     pub matcher_token_account: Account<'info, TokenAccount>,
 
+    /// Required when the market has a match filter configured
+    #[account(
+        seeds = [b"match_filter", market.key().as_ref()],
+        bump = match_filter.bump
+    )]
+    pub match_filter: Option<Account<'info, MatchFilter>>,
+
+    /// The Pyth price update account. Required for percentage-move and
+    /// relative-performance bets, which record the current price as their reference.
+    #[account(
+        address = market.load_market()?.oracle_account,
+    )]
+    pub price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    /// Second market and its Pyth price update, required for relative-performance
+    /// bets, which count as open bets on both markets until they settle
+    #[account(mut)]
+    pub other_market: Option<AccountLoader<'info, BettingMarket>>,
+    pub other_price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", matcher.key().as_ref()],
+        bump = matcher_profile.bump
+    )]
+    pub matcher_profile: Account<'info, UserProfile>,
+
+    #[account(
+        init,
+        payer = matcher,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", matcher.key().as_ref(), &matcher_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub matcher_bet_index: Account<'info, UserBetIndex>,
+
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn match_bet(ctx: Context<MatchBet>) -> Result<()> {
+/// Enforce the market's allowlist/denylist if it has one
+pub(crate) fn enforce_match_filter(
+    market: &BettingMarket,
+    match_filter: Option<&Account<MatchFilter>>,
+    matcher: &Pubkey,
+) -> Result<()> {
+    if market.match_filter != Pubkey::default() {
+        let match_filter = match_filter.ok_or(ErrorCode::InvalidMatchFilter)?;
+        require_keys_eq!(
+            match_filter.key(),
+            market.match_filter,
+            ErrorCode::InvalidMatchFilter
+        );
+        require!(match_filter.allows(matcher), ErrorCode::MatcherNotAllowed);
+    }
+    Ok(())
+}
+
+pub fn match_bet(
+    ctx: Context<MatchBet>,
+    expected_recurrence_interval: i64,
+    expected_remaining_rounds: u16,
+) -> Result<()> {
     let bet = &mut ctx.accounts.bet;
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    // Ensure the market still accepts matches
+    market.require_active()?;
 
     // Ensure bet is not already matched
     require!(!bet.is_matched, ErrorCode::BetAlreadyMatched);
@@ -49,10 +130,53 @@ pub fn match_bet(ctx: Context<MatchBet>) -> Result<()> {
     // Ensure bet is funded before it can be matched
     require!(bet.is_funded, ErrorCode::BetNotFunded);
 
+    // The better can change the schedule until the bet is matched, so the
+    // matcher states the one they're agreeing to
+    require!(
+        bet.recurrence_interval == expected_recurrence_interval
+            && bet.remaining_rounds == expected_remaining_rounds,
+        ErrorCode::RecurrenceMismatch
+    );
+
+    // Prevent wash trades: the better and whoever funded the bet can't take the other side
+    let matcher = ctx.accounts.matcher.key();
+    require!(
+        matcher != bet.better && matcher != bet.funder,
+        ErrorCode::SelfMatch
+    );
+
+    enforce_match_filter(&market, ctx.accounts.match_filter.as_ref(), &matcher)?;
+
     // Ensure the current time is before settlement time
     let current_time = Clock::get()?.unix_timestamp;
     require!(current_time < bet.settlement_time, ErrorCode::BetExpired);
 
+    market.check_stake(bet.amount)?;
+
+    // Percentage-move and relative-performance bets are measured from the
+    // price when they're matched
+    if bet.price_direction.is_relative() {
+        let price_update = ctx
+            .accounts
+            .price_update
+            .as_ref()
+            .ok_or(ErrorCode::PriceFeedLoadError)?;
+        bet.reference_price = normalize_price(&market_price(price_update, &market)?)?;
+        require!(bet.reference_price > 0, ErrorCode::PriceConversionError);
+    }
+    if let Some(other_market) = bet.price_direction.other_market() {
+        let mut other = load_other_market(ctx.accounts.other_market.as_ref(), other_market)?
+            .load_market_mut()?;
+        other.require_active()?;
+        bet.other_reference_price =
+            other_market_price(&other, ctx.accounts.other_price_update.as_ref())?;
+        require!(
+            bet.other_reference_price > 0,
+            ErrorCode::PriceConversionError
+        );
+        other.add_open_bet();
+    }
+
     // Transfer USDC from matcher to bet escrow
     let cpi_accounts = Transfer {
         from: ctx.accounts.matcher_token_account.to_account_info(),
@@ -65,10 +189,33 @@ pub fn match_bet(ctx: Context<MatchBet>) -> Result<()> {
     // Update bet
     bet.is_matched = true;
     bet.matcher = Some(ctx.accounts.matcher.key());
-
-    // Update market statistics
-    market.total_volume = market.total_volume.checked_add(bet.amount).unwrap();
-    market.total_matched_count = market.total_matched_count.checked_add(1).unwrap();
+    bet.matched_at = current_time;
+
+    // Update market statistics. Volume counts both stakes once a bet is
+    // matched, so bets closed unmatched never touch it.
+    market.record_volume(current_time, bet.amount.checked_mul(2).unwrap());
+    market.add_open_interest(bet.amount)?;
+    market.record_match(current_time);
+
+    let matcher_profile = &mut ctx.accounts.matcher_profile;
+    matcher_profile.bets_matched = matcher_profile.bets_matched.checked_add(1).unwrap();
+
+    // Record the bet in the matcher's bet index
+    let matcher_bet_index = &mut ctx.accounts.matcher_bet_index;
+    matcher_bet_index.owner = matcher_profile.owner;
+    matcher_bet_index.index = matcher_profile.next_bet_index();
+    matcher_bet_index.bet = bet.key();
+    matcher_bet_index.bump = ctx.bumps.matcher_bet_index;
+
+    emit!(BetMatched {
+        bet: bet.key(),
+        market: ctx.accounts.market.key(),
+        better: bet.better,
+        matcher,
+        amount: bet.amount,
+        reference_price: bet.reference_price,
+        other_reference_price: bet.other_reference_price,
+    });
 
     msg!(
         "Bet matched by {}: {} USDC on {}",
@@ -0,0 +1,109 @@
+use crate::errors::ErrorCode;
+use crate::instructions::create_parlay::leg_markets;
+use crate::instructions::match_bet::enforce_match_filter;
+use crate::state::{MarketLoader, MatchFilter, Parlay};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct ParlayMatched {
+    pub parlay: Pubkey,
+    pub better: Pubkey,
+    pub matcher: Pubkey,
+    pub amount: u64,
+    pub matcher_amount: u64,
+}
+
+/// Takes the other side of a whole parlay. The legs' markets are passed as
+/// writable remaining accounts, in leg order, followed by the match filter of
+/// each leg whose market has one, in the same order.
+#[derive(Accounts)]
+pub struct MatchParlay<'info> {
+    #[account(mut)]
+    pub matcher: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"parlay", parlay.better.as_ref(), &parlay.parlay_id.to_le_bytes()],
+        bump = parlay.bump
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    #[account(
+        mut,
+        constraint = parlay_escrow.key() == parlay.escrow
+    )]
+    pub parlay_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub matcher_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn match_parlay<'info>(ctx: Context<'_, '_, 'info, 'info, MatchParlay<'info>>) -> Result<()> {
+    let parlay = &mut ctx.accounts.parlay;
+
+    require!(!parlay.is_matched, ErrorCode::BetAlreadyMatched);
+
+    let matcher = ctx.accounts.matcher.key();
+    require!(matcher != parlay.better, ErrorCode::SelfMatch);
+
+    // Every leg must still be open
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time < parlay.first_settlement_time(),
+        ErrorCode::BetExpired
+    );
+    let (market_accounts, filter_accounts) = ctx
+        .remaining_accounts
+        .split_at(parlay.legs.len().min(ctx.remaining_accounts.len()));
+    let mut filter_accounts = filter_accounts.iter();
+    for market in leg_markets(market_accounts, &parlay.legs)? {
+        let mut market = market.load_market_mut()?;
+        market.require_active()?;
+        market.check_stake(parlay.matcher_amount)?;
+
+        // Each market's filter applies as if the leg were a bet of its own
+        let match_filter = if market.match_filter != Pubkey::default() {
+            let account = filter_accounts
+                .next()
+                .ok_or(ErrorCode::InvalidMatchFilter)?;
+            Some(Account::<MatchFilter>::try_from(account)?)
+        } else {
+            None
+        };
+        enforce_match_filter(&market, match_filter.as_ref(), &matcher)?;
+
+        market.add_open_interest(parlay.matcher_amount)?;
+    }
+
+    // Transfer USDC from matcher to parlay escrow
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.matcher_token_account.to_account_info(),
+        to: ctx.accounts.parlay_escrow.to_account_info(),
+        authority: ctx.accounts.matcher.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, parlay.matcher_amount)?;
+
+    parlay.is_matched = true;
+    parlay.matcher = Some(matcher);
+
+    emit!(ParlayMatched {
+        parlay: parlay.key(),
+        better: parlay.better,
+        matcher,
+        amount: parlay.amount,
+        matcher_amount: parlay.matcher_amount,
+    });
+
+    msg!(
+        "Parlay matched by {}: {} USDC against {} USDC",
+        matcher,
+        parlay.matcher_amount / 1_000_000,
+        parlay.amount / 1_000_000
+    );
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use crate::errors::ErrorCode;
+use crate::state::{Bet, BettingMarket, LegacyBettingMarket, MarketLoader, SettlementRound};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: A market, bet or settlement round in an older layout, which
+    /// can't be loaded as `Account<_>` until it has been resized. Ownership is
+    /// checked here and the discriminator in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    /// The bet's market, required when migrating a bet
+    pub market: Option<AccountLoader<'info, BettingMarket>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a market, bet or settlement round created with an older layout to the current one and
+/// upgrade its contents. Bets only ever gain appended fields, so the resize
+/// zero-fills them and the existing bytes keep their meaning. Markets moved
+/// from Borsh to a zero-copy layout in v2, so they are decoded in the legacy
+/// layout and rewritten. Markets keep their original address, so existing
+/// bets are unaffected. Settlement rounds gained TWAP sample fields.
+pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account_info = ctx.accounts.account.to_account_info();
+
+    let discriminator: [u8; 8] = {
+        let data = account_info.try_borrow_data()?;
+        require!(data.len() >= 8, ErrorCode::UnsupportedAccount);
+        data[..8].try_into().unwrap()
+    };
+
+    if discriminator == BettingMarket::DISCRIMINATOR {
+        // Zero-copy markets are never smaller than the legacy layout
+        require!(
+            account_info.data_len() < BettingMarket::LEN,
+            ErrorCode::AccountAlreadyMigrated
+        );
+
+        // Pre-v1 markets are shorter still; zero-fill them up to the v1 size
+        resize_account(ctx.accounts, &account_info, LegacyBettingMarket::LEN)?;
+        let legacy = {
+            let data = account_info.try_borrow_data()?;
+            LegacyBettingMarket::deserialize(&mut &data[8..])?
+        };
+
+        resize_account(ctx.accounts, &account_info, BettingMarket::LEN)?;
+        {
+            let mut data = account_info.try_borrow_mut_data()?;
+            data[8..].fill(0);
+            let market: &mut BettingMarket = bytemuck::from_bytes_mut(&mut data[8..]);
+            market.migrate_from(&legacy);
+        }
+
+        msg!(
+            "Market {} migrated to v{}",
+            account_info.key(),
+            BettingMarket::VERSION
+        );
+    } else if discriminator == Bet::DISCRIMINATOR {
+        resize_account(ctx.accounts, &account_info, Bet::LEN)?;
+
+        let mut data = account_info.try_borrow_mut_data()?;
+        let mut bet = Bet::try_deserialize(&mut &data[..])?;
+        require!(
+            bet.version < Bet::VERSION,
+            ErrorCode::AccountAlreadyMigrated
+        );
+        let market = ctx
+            .accounts
+            .market
+            .as_ref()
+            .ok_or(ErrorCode::InvalidMarket)?;
+        require_keys_eq!(market.key(), bet.market, ErrorCode::InvalidMarket);
+        bet.upgrade(&*market.load_market()?);
+        bet.try_serialize(&mut &mut data[..])?;
+
+        msg!("Bet {} migrated to v{}", account_info.key(), bet.version);
+    } else if discriminator == SettlementRound::DISCRIMINATOR {
+        // Rounds have no version; ones recorded before TWAP support are shorter
+        require!(
+            account_info.data_len() < SettlementRound::LEN,
+            ErrorCode::AccountAlreadyMigrated
+        );
+        resize_account(ctx.accounts, &account_info, SettlementRound::LEN)?;
+
+        let mut data = account_info.try_borrow_mut_data()?;
+        let mut settlement_round = SettlementRound::try_deserialize(&mut &data[..])?;
+        // The single recorded price becomes the round's only sample
+        settlement_round.sample_count = 1;
+        settlement_round.price_sum = settlement_round.price as u128;
+        settlement_round.try_serialize(&mut &mut data[..])?;
+
+        msg!("Settlement round {} migrated", account_info.key());
+    } else {
+        return err!(ErrorCode::UnsupportedAccount);
+    }
+
+    Ok(())
+}
+
+/// Grow an account to `len` bytes, topping up rent from the payer
+fn resize_account<'info>(
+    accounts: &MigrateAccount<'info>,
+    account_info: &AccountInfo<'info>,
+    len: usize,
+) -> Result<()> {
+    if account_info.data_len() >= len {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(len);
+    let shortfall = rent.saturating_sub(account_info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.payer.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    account_info.realloc(len, true)?;
+    Ok(())
+}
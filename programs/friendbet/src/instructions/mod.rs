@@ -1,17 +1,91 @@
+pub mod add_settlement_sample;
+pub mod backfill_open_bet_count;
+pub mod cancel_parlay;
+pub mod claim_bucket_pool;
 pub mod claim_funds;
+pub mod claim_parlay;
+pub mod claim_pool;
 pub mod close_bet;
+pub mod close_market;
+pub mod close_offer;
 pub mod create_bet;
 pub mod create_bet_for_user;
+pub mod create_bucket_pool;
+pub mod create_offer;
+pub mod create_parlay;
+pub mod create_pool;
+pub mod deactivate_market;
+pub mod enter_bucket_pool;
+pub mod enter_pool;
 pub mod fund_bet;
 pub mod initialize_market;
+pub mod initialize_match_filter;
+pub mod initialize_profile;
+pub mod initialize_registry;
 pub mod match_bet;
+pub mod match_parlay;
+pub mod migrate_account;
+pub mod record_settlement_round;
+pub mod register_market;
+pub mod roll_over_bet;
+pub mod set_bet_recurrence;
 pub mod settle_bet;
+pub mod settle_bucket_pool;
+pub mod settle_parlay_leg;
+pub mod settle_pool;
+pub mod stop_bet_recurrence;
+pub mod take_offer;
+pub mod trigger_barrier;
+pub mod update_market_limits;
+pub mod update_match_filter;
+pub mod update_price_source;
+pub mod update_settlement_bounds;
+pub mod void_bet;
+pub mod void_bucket_pool;
+pub mod void_pool;
 
+pub use add_settlement_sample::*;
+pub use backfill_open_bet_count::*;
+pub use cancel_parlay::*;
+pub use claim_bucket_pool::*;
 pub use claim_funds::*;
+pub use claim_parlay::*;
+pub use claim_pool::*;
 pub use close_bet::*;
+pub use close_market::*;
+pub use close_offer::*;
 pub use create_bet::*;
 pub use create_bet_for_user::*;
+pub use create_bucket_pool::*;
+pub use create_offer::*;
+pub use create_parlay::*;
+pub use create_pool::*;
+pub use deactivate_market::*;
+pub use enter_bucket_pool::*;
+pub use enter_pool::*;
 pub use fund_bet::*;
 pub use initialize_market::*;
+pub use initialize_match_filter::*;
+pub use initialize_profile::*;
+pub use initialize_registry::*;
 pub use match_bet::*;
+pub use match_parlay::*;
+pub use migrate_account::*;
+pub use record_settlement_round::*;
+pub use register_market::*;
+pub use roll_over_bet::*;
+pub use set_bet_recurrence::*;
 pub use settle_bet::*;
+pub use settle_bucket_pool::*;
+pub use settle_parlay_leg::*;
+pub use settle_pool::*;
+pub use stop_bet_recurrence::*;
+pub use take_offer::*;
+pub use trigger_barrier::*;
+pub use update_market_limits::*;
+pub use update_match_filter::*;
+pub use update_price_source::*;
+pub use update_settlement_bounds::*;
+pub use void_bet::*;
+pub use void_bucket_pool::*;
+pub use void_pool::*;
@@ -0,0 +1,80 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::{normalize_price, verified_price, STALENESS_THRESHOLD};
+use crate::state::{BettingMarket, MarketLoader, SettlementRound};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+#[instruction(settlement_time: i64)]
+pub struct RecordSettlementRound<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = SettlementRound::LEN,
+        seeds = [b"round", market.key().as_ref(), &settlement_time.to_le_bytes()],
+        bump
+    )]
+    pub settlement_round: Account<'info, SettlementRound>,
+
+    /// Any fully verified Pyth update for the market's feed, so a round that
+    /// was missed can still be recorded later from a historical update
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn record_settlement_round(
+    ctx: Context<RecordSettlementRound>,
+    settlement_time: i64,
+) -> Result<()> {
+    let market = ctx.accounts.market.load_market()?;
+
+    // Rounds aren't limited to the market's current schedule: bets fix whether
+    // they settle on a round when created, and a later schedule change
+    // mustn't leave them without one
+
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= settlement_time,
+        ErrorCode::SettlementTimeTooEarly
+    );
+
+    let pyth_price = verified_price(&ctx.accounts.price_update, &market)?;
+
+    // Only a price from at or after the slot counts as the official slot price
+    require!(
+        pyth_price.publish_time >= settlement_time,
+        ErrorCode::PriceBeforeSettlementSlot
+    );
+    // ...and only one from shortly after it. Averaged rounds take their first
+    // sample at the slot too, or a late one would leave no room for the rest.
+    require!(
+        pyth_price.publish_time <= settlement_time + STALENESS_THRESHOLD as i64,
+        ErrorCode::PriceAfterSettlementSlot
+    );
+
+    let settlement_round = &mut ctx.accounts.settlement_round;
+    settlement_round.market = ctx.accounts.market.key();
+    settlement_round.settlement_time = settlement_time;
+    settlement_round.add_sample(normalize_price(&pyth_price)?, pyth_price.publish_time);
+    settlement_round.recorded_by = ctx.accounts.payer.key();
+    settlement_round.bump = ctx.bumps.settlement_round;
+
+    msg!(
+        "Settlement round recorded for {} at {}: price {}",
+        market.get_token_name(),
+        settlement_time,
+        settlement_round.price
+    );
+
+    Ok(())
+}
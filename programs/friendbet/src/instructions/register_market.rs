@@ -0,0 +1,32 @@
+use crate::state::{BettingMarket, MarketLoader, MarketRegistry};
+use anchor_lang::prelude::*;
+
+/// Adds a market that predates the registry
+#[derive(Accounts)]
+pub struct RegisterMarket<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, MarketRegistry>,
+}
+
+pub fn register_market(ctx: Context<RegisterMarket>) -> Result<()> {
+    let market = ctx.accounts.market.load_market()?;
+    ctx.accounts
+        .registry
+        .register(ctx.accounts.market.key(), &market)?;
+
+    msg!("Market {} registered", market.get_token_name());
+
+    Ok(())
+}
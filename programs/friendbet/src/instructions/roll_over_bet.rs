@@ -0,0 +1,236 @@
+use crate::errors::ErrorCode;
+use crate::state::{Bet, BettingMarket, MarketLoader, UserBetIndex, UserProfile};
+use crate::USDC_MINT;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use std::str::FromStr;
+
+#[event]
+pub struct BetRolledOver {
+    pub bet: Pubkey,
+    pub next_bet: Pubkey,
+    pub market: Pubkey,
+    pub amount: u64,
+    pub settlement_time: i64,
+    pub remaining_rounds: u16,
+}
+
+/// Opens the next round of a settled recurring bet. Anyone can call it.
+/// Both parties pre-approve their rollover PDA, `[b"rollover", wallet]`, as
+/// delegate on their USDC account, and each round's stakes are drawn from
+/// that allowance. Opens `ROLLOVER_DELAY` after the round's settlement time,
+/// so either party can still stop the series once the result is known, and
+/// must run before the settled bet is claimed, which closes it.
+#[derive(Accounts)]
+pub struct RollOverBet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration
+    )]
+    pub bet: Box<Account<'info, Bet>>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == bet.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Bet::LEN,
+        seeds = [b"bet", market.key().as_ref(), &(market.load_market()?.bet_count + 1).to_le_bytes()],
+        bump
+    )]
+    pub next_bet: Box<Account<'info, Bet>>,
+
+    #[account(
+        constraint = usdc_mint.key() == Pubkey::from_str(USDC_MINT).unwrap()
+    )]
+    pub usdc_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = next_bet,
+    )]
+    pub next_bet_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = better_token_account.mint == usdc_mint.key(),
+        constraint = better_token_account.owner == bet.better
+    )]
+    pub better_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = matcher_token_account.mint == usdc_mint.key(),
+        constraint = Some(matcher_token_account.owner) == bet.matcher
+    )]
+    pub matcher_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: PDA the better approved as delegate, only used to sign the transfer
+    #[account(
+        seeds = [b"rollover", bet.better.as_ref()],
+        bump
+    )]
+    pub better_rollover_authority: UncheckedAccount<'info>,
+
+    /// CHECK: PDA the matcher approved as delegate, only used to sign the transfer
+    #[account(
+        seeds = [b"rollover", bet.matcher.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub matcher_rollover_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.better.as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", bet.better.as_ref(), &better_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub better_bet_index: Box<Account<'info, UserBetIndex>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.matcher.unwrap_or_default().as_ref()],
+        bump = matcher_profile.bump
+    )]
+    pub matcher_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", bet.matcher.unwrap_or_default().as_ref(), &matcher_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub matcher_bet_index: Box<Account<'info, UserBetIndex>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn roll_over_bet(ctx: Context<RollOverBet>) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    require!(bet.is_settled, ErrorCode::BetNotSettled);
+    require!(bet.recurs(), ErrorCode::BetDoesNotRecur);
+
+    // Either party may still opt out until the rollover window opens
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= bet.rollover_opens_at(),
+        ErrorCode::RolloverTooEarly
+    );
+
+    // The next round must still be a bet the market would accept today
+    let next_round = bet.next_round();
+    market.require_active()?;
+    market.check_settlement_time(next_round.settlement_time, current_time)?;
+    market.check_stake(next_round.amount)?;
+
+    // Draw both stakes from the parties' pre-approved allowances
+    let matcher = bet.matcher.unwrap();
+    for (wallet, from, authority, bump) in [
+        (
+            bet.better,
+            &ctx.accounts.better_token_account,
+            &ctx.accounts.better_rollover_authority,
+            ctx.bumps.better_rollover_authority,
+        ),
+        (
+            matcher,
+            &ctx.accounts.matcher_token_account,
+            &ctx.accounts.matcher_rollover_authority,
+            ctx.bumps.matcher_rollover_authority,
+        ),
+    ] {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: ctx.accounts.next_bet_escrow.to_account_info(),
+                    authority: authority.to_account_info(),
+                },
+                &[&[b"rollover".as_ref(), wallet.as_ref(), &[bump]]],
+            ),
+            next_round.amount,
+        )?;
+        market.record_volume(current_time, next_round.amount);
+        market.add_open_interest(next_round.amount)?;
+    }
+
+    let bet_count = market.bet_count + 1;
+    let next_bet = &mut ctx.accounts.next_bet;
+    next_bet.set_inner(Bet {
+        escrow: ctx.accounts.next_bet_escrow.key(),
+        bet_count,
+        matched_at: current_time,
+        settles_on_round: market.is_settlement_slot(next_round.settlement_time),
+        bump: ctx.bumps.next_bet,
+        ..next_round
+    });
+
+    // This round has been rolled over, so it can't be again
+    bet.remaining_rounds = 0;
+
+    // Update market stats
+    market.bet_count = bet_count;
+    market.add_open_bet();
+    market.record_match(current_time);
+
+    // Record the new round in both parties' profiles and bet indexes
+    let better_profile = &mut ctx.accounts.better_profile;
+    better_profile.bets_created = better_profile.bets_created.checked_add(1).unwrap();
+    let better_bet_index = &mut ctx.accounts.better_bet_index;
+    better_bet_index.owner = better_profile.owner;
+    better_bet_index.index = better_profile.next_bet_index();
+    better_bet_index.bet = next_bet.key();
+    better_bet_index.bump = ctx.bumps.better_bet_index;
+
+    let matcher_profile = &mut ctx.accounts.matcher_profile;
+    matcher_profile.bets_matched = matcher_profile.bets_matched.checked_add(1).unwrap();
+    let matcher_bet_index = &mut ctx.accounts.matcher_bet_index;
+    matcher_bet_index.owner = matcher_profile.owner;
+    matcher_bet_index.index = matcher_profile.next_bet_index();
+    matcher_bet_index.bet = next_bet.key();
+    matcher_bet_index.bump = ctx.bumps.matcher_bet_index;
+
+    emit!(BetRolledOver {
+        bet: bet.key(),
+        next_bet: next_bet.key(),
+        market: ctx.accounts.market.key(),
+        amount: next_bet.amount,
+        settlement_time: next_bet.settlement_time,
+        remaining_rounds: next_bet.remaining_rounds,
+    });
+
+    msg!(
+        "Recurring bet rolled over on {}: {} USDC each, settling at {} ({} rounds left)",
+        market.get_token_name(),
+        next_bet.amount / 1_000_000,
+        next_bet.settlement_time,
+        next_bet.remaining_rounds
+    );
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::errors::ErrorCode;
+use crate::state::{Bet, BettingMarket, MarketLoader};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct BetRecurrenceSet {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub recurrence_interval: i64,
+    pub remaining_rounds: u16,
+}
+
+/// Makes an unmatched bet recur. Whoever matches it agrees to the schedule.
+#[derive(Accounts)]
+pub struct SetBetRecurrence<'info> {
+    pub better: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", bet.market.as_ref(), &bet.bet_count.to_le_bytes()],
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration,
+        constraint = bet.better == better.key() @ ErrorCode::InvalidBetter,
+        constraint = !bet.is_matched @ ErrorCode::BetAlreadyMatched
+    )]
+    pub bet: Account<'info, Bet>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == bet.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+}
+
+pub fn set_bet_recurrence(
+    ctx: Context<SetBetRecurrence>,
+    recurrence_interval: i64,
+    remaining_rounds: u16,
+) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+
+    // Later rounds are paid from the parties' own wallets and matched
+    // without a fresh price, so only plain self-funded bets can recur
+    require!(
+        recurrence_interval > 0
+            && remaining_rounds > 0
+            && bet.is_funded
+            && bet.funder == bet.better
+            && !bet.price_direction.is_relative(),
+        ErrorCode::InvalidRecurrence
+    );
+
+    // Each round must be one the market would accept once it can be rolled
+    // over, on the same settlement slots as this one
+    let next_settlement_time = bet
+        .settlement_time
+        .checked_add(recurrence_interval)
+        .ok_or(ErrorCode::InvalidRecurrence)?;
+    let market = ctx.accounts.market.load_market()?;
+    market.check_settlement_time(next_settlement_time, bet.rollover_opens_at())?;
+
+    bet.recurrence_interval = recurrence_interval;
+    bet.remaining_rounds = remaining_rounds;
+
+    emit!(BetRecurrenceSet {
+        bet: bet.key(),
+        market: bet.market,
+        recurrence_interval,
+        remaining_rounds,
+    });
+
+    msg!(
+        "Bet set to recur every {} seconds for {} more rounds on {}",
+        recurrence_interval,
+        remaining_rounds,
+        market.get_token_name()
+    );
+
+    Ok(())
+}
@@ -1,12 +1,11 @@
 use crate::errors::ErrorCode;
 use crate::state::PriceDirection;
-use crate::state::{Bet, BettingMarket};
+use crate::state::{Bet, BettingMarket, MarketLoader, PriceSource, SettlementRound, UserProfile};
 use anchor_lang::prelude::*;
-use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
-use std::cmp::Ordering;
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2, VerificationLevel};
 
 // Define a constant for the staleness threshold (e.g., 60 seconds)
-const STALENESS_THRESHOLD: u64 = 60;
+pub(crate) const STALENESS_THRESHOLD: u64 = 60;
 
 // Define an event for bet settlement
 #[event]
@@ -17,7 +16,11 @@ pub struct BetSettled {
     pub current_price: u64,
     pub price_threshold: u64,
     pub price_direction: PriceDirection,
+    pub reference_price: u64,
+    pub other_price: u64,
+    pub other_reference_price: u64,
     pub winner: Pubkey,
+    pub memo: String,
 }
 
 #[derive(Accounts)]
@@ -27,28 +30,131 @@ pub struct SettleBet<'info> {
     #[account(
         mut,
         seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
-        bump = bet.bump
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration
     )]
     pub bet: Account<'info, Bet>,
 
     #[account(
         mut,
-        seeds = [b"market", &market.feed_id[..8]],
-        bump = market.bump,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
         constraint = market.key() == bet.market
     )]
-    pub market: Account<'info, BettingMarket>,
+    pub market: AccountLoader<'info, BettingMarket>,
 
-    /// The Pyth price update account
+    /// The Pyth price update account. Not needed when settling against a round.
     #[account(
-        address = market.oracle_account,
+        address = market.load_market()?.oracle_account,
     )]
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    /// Second market of a relative-performance bet, with its Pyth price update
+    /// or, if the bet settles on rounds, its round for the same slot
+    #[account(mut)]
+    pub other_market: Option<AccountLoader<'info, BettingMarket>>,
+    pub other_price_update: Option<Account<'info, PriceUpdateV2>>,
+    #[account(
+        constraint = other_settlement_round.settlement_time == bet.settlement_time @ ErrorCode::InvalidSettlementRound
+    )]
+    pub other_settlement_round: Option<Account<'info, SettlementRound>>,
+
+    /// The recorded price for the bet's settlement slot, if it settles on one
+    #[account(
+        seeds = [b"round", market.key().as_ref(), &bet.settlement_time.to_le_bytes()],
+        bump = settlement_round.bump
+    )]
+    pub settlement_round: Option<Account<'info, SettlementRound>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.better.as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.matcher.unwrap_or_default().as_ref()],
+        bump = matcher_profile.bump
+    )]
+    pub matcher_profile: Account<'info, UserProfile>,
+
+    /// Profile of a sponsor who funded the better's stake, required when there is one
+    #[account(
+        mut,
+        seeds = [b"profile", bet.funder.as_ref()],
+        bump = sponsor_profile.bump,
+        constraint = bet.sponsor().is_some() @ ErrorCode::InvalidFunder
+    )]
+    pub sponsor_profile: Option<Account<'info, UserProfile>>,
+}
+
+/// Convert a Pyth price to u64 format (normalize based on exponent)
+pub(crate) fn normalize_price(pyth_price: &Price) -> Result<u64> {
+    let price_exponent = pyth_price.exponent;
+    let price = if price_exponent >= 0 {
+        u64::try_from(pyth_price.price).map_err(|_| ErrorCode::PriceConversionError)?
+            * 10u64.pow(price_exponent as u32)
+    } else {
+        u64::try_from(pyth_price.price).map_err(|_| ErrorCode::PriceConversionError)?
+            / 10u64.pow((-price_exponent) as u32)
+    };
+    Ok(price)
+}
+
+/// Current price from `price_update` for the market's feed, rejecting stale
+/// updates and using the market's price source
+pub(crate) fn market_price(price_update: &PriceUpdateV2, market: &BettingMarket) -> Result<Price> {
+    let pyth_price = price_update.get_price_no_older_than(
+        &Clock::get()?,
+        STALENESS_THRESHOLD,
+        &market.feed_id,
+    )?;
+    Ok(with_price_source(
+        price_update,
+        pyth_price,
+        market.price_source(),
+    ))
+}
+
+/// Price from any fully verified update for the market's feed, however old.
+/// Callers check `publish_time` against the time they need a price for.
+pub(crate) fn verified_price(
+    price_update: &PriceUpdateV2,
+    market: &BettingMarket,
+) -> Result<Price> {
+    // Historical updates skip the staleness check, so insist on full verification
+    require!(
+        price_update.verification_level == VerificationLevel::Full,
+        ErrorCode::UnverifiedPriceUpdate
+    );
+    Ok(with_price_source(
+        price_update,
+        price_update.get_price_unchecked(&market.feed_id)?,
+        market.price_source(),
+    ))
+}
+
+/// Swap in the EMA price for markets that settle on it
+pub(crate) fn with_price_source(
+    price_update: &PriceUpdateV2,
+    pyth_price: Price,
+    price_source: PriceSource,
+) -> Price {
+    match price_source {
+        PriceSource::Spot => pyth_price,
+        PriceSource::Ema => Price {
+            price: price_update.price_message.ema_price,
+            conf: price_update.price_message.ema_conf,
+            ..pyth_price
+        },
+    }
 }
 
 pub fn settle_bet(ctx: Context<SettleBet>) -> Result<()> {
     let bet = &mut ctx.accounts.bet;
-    let market = &mut ctx.accounts.market;
+    let mut market = ctx.accounts.market.load_market_mut()?;
 
     // Ensure bet is matched
     require!(bet.is_matched, ErrorCode::BetNotMatched);
@@ -63,61 +169,185 @@ pub fn settle_bet(ctx: Context<SettleBet>) -> Result<()> {
         ErrorCode::SettlementTimeTooEarly
     );
 
-    // Get the price update account
-    let price_update = &ctx.accounts.price_update;
+    let current_price = settlement_price(
+        &ctx.accounts.market.key(),
+        &market,
+        bet.settles_on_round,
+        ctx.accounts.settlement_round.as_deref(),
+        ctx.accounts.price_update.as_deref(),
+        current_time,
+    )?;
 
-    // Use the feed ID directly from the market (already in byte array format)
-    let feed_id = &market.feed_id;
+    // Relative-performance bets also need the second market's price, taken
+    // the same way as the first: from its round for the same slot, or now
+    let other_price = match bet.price_direction.other_market() {
+        Some(other_market) => {
+            let other_market = load_other_market(ctx.accounts.other_market.as_ref(), other_market)?;
+            let mut other = other_market.load_market_mut()?;
+            let other_price = settlement_price(
+                &other_market.key(),
+                &other,
+                bet.settles_on_round,
+                ctx.accounts.other_settlement_round.as_deref(),
+                ctx.accounts.other_price_update.as_deref(),
+                current_time,
+            )?;
+            // The bet no longer depends on the second market
+            other.remove_open_bet()?;
+            other_price
+        }
+        None => 0,
+    };
 
-    // Get current price and ensure it's not stale
-    let pyth_price =
-        price_update.get_price_no_older_than(&Clock::get()?, STALENESS_THRESHOLD, feed_id)?;
+    settle_at_price(
+        bet,
+        ctx.accounts.market.key(),
+        &mut market,
+        &mut ctx.accounts.better_profile,
+        &mut ctx.accounts.matcher_profile,
+        ctx.accounts.sponsor_profile.as_mut(),
+        current_price,
+        other_price,
+        current_time,
+    )?;
 
-    // Convert the price to u64 format (normalize based on exponent)
-    let price_exponent = pyth_price.exponent;
-    let current_price = if price_exponent >= 0 {
-        u64::try_from(pyth_price.price).map_err(|_| ErrorCode::PriceConversionError)?
-            * 10u64.pow(price_exponent as u32)
+    Ok(())
+}
+
+/// Price a condition on `market` settles at, from its slot's round if it
+/// settles on one and from a current update otherwise
+pub(crate) fn settlement_price(
+    market_key: &Pubkey,
+    market: &BettingMarket,
+    on_round: bool,
+    settlement_round: Option<&SettlementRound>,
+    price_update: Option<&PriceUpdateV2>,
+    current_time: i64,
+) -> Result<u64> {
+    if on_round {
+        // Scheduled bets all settle against the price recorded once for their slot
+        let settlement_round = settlement_round.ok_or(ErrorCode::SettlementRoundRequired)?;
+        round_price(settlement_round, market_key, market, current_time)
     } else {
-        u64::try_from(pyth_price.price).map_err(|_| ErrorCode::PriceConversionError)?
-            / 10u64.pow((-price_exponent) as u32)
-    };
+        // Get the price update account
+        let price_update = price_update.ok_or(ErrorCode::PriceFeedLoadError)?;
+
+        // Get current price and ensure it's not stale
+        normalize_price(&market_price(price_update, market)?)
+    }
+}
 
+/// Price recorded for a settlement slot. Averaged rounds can only be used
+/// once their window has closed.
+pub(crate) fn round_price(
+    settlement_round: &SettlementRound,
+    market_key: &Pubkey,
+    market: &BettingMarket,
+    current_time: i64,
+) -> Result<u64> {
+    require_keys_eq!(
+        settlement_round.market,
+        *market_key,
+        ErrorCode::InvalidSettlementRound
+    );
+    require!(
+        settlement_round.is_complete(market, current_time),
+        ErrorCode::TwapIncomplete
+    );
+    Ok(settlement_round.price)
+}
+
+/// Second market of a relative-performance bet, checked against the one it names
+pub(crate) fn load_other_market<'a, 'info>(
+    other_market: Option<&'a AccountLoader<'info, BettingMarket>>,
+    expected_market: Pubkey,
+) -> Result<&'a AccountLoader<'info, BettingMarket>> {
+    let other_market = other_market.ok_or(ErrorCode::InvalidOtherMarket)?;
+    require_keys_eq!(
+        other_market.key(),
+        expected_market,
+        ErrorCode::InvalidOtherMarket
+    );
+    Ok(other_market)
+}
+
+/// Current price on the second market of a relative-performance bet
+pub(crate) fn other_market_price(
+    other_market: &BettingMarket,
+    other_price_update: Option<&Account<PriceUpdateV2>>,
+) -> Result<u64> {
+    let price_update = other_price_update.ok_or(ErrorCode::PriceFeedLoadError)?;
+    require_keys_eq!(
+        price_update.key(),
+        other_market.oracle_account,
+        ErrorCode::PriceFeedLoadError
+    );
+    normalize_price(&market_price(price_update, other_market)?)
+}
+
+/// Settle a matched bet at `current_price`, updating both sides' profiles and
+/// the market's stats. `other_price` is only used by relative-performance bets.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_at_price(
+    bet: &mut Account<Bet>,
+    market_key: Pubkey,
+    market: &mut BettingMarket,
+    better_profile: &mut Account<UserProfile>,
+    matcher_profile: &mut Account<UserProfile>,
+    sponsor_profile: Option<&mut Account<UserProfile>>,
+    current_price: u64,
+    other_price: u64,
+    current_time: i64,
+) -> Result<()> {
     // Determine the winner based on the price condition
-    let winner = match bet.price_direction {
-        PriceDirection::Above => match current_price.cmp(&bet.price_threshold) {
-            Ordering::Greater => bet.better,
-            _ => bet.matcher.unwrap(),
-        },
-        PriceDirection::Below => match current_price.cmp(&bet.price_threshold) {
-            Ordering::Less => bet.better,
-            _ => bet.matcher.unwrap(),
-        },
+    let winner = if bet.better_wins(current_price, other_price) {
+        bet.better
+    } else {
+        bet.matcher.unwrap()
     };
 
     // Update bet
     bet.is_settled = true;
     bet.winner = Some(winner);
 
+    // Update both sides' lifetime stats. The better's stake is charged to
+    // whoever funded it.
+    let better_won = winner == bet.better;
+    better_profile.record_result(better_won);
+    matcher_profile.record_settlement(!better_won, bet.amount);
+    if bet.funder == bet.better {
+        better_profile.record_stake(bet.amount);
+    } else if bet.matcher == Some(bet.funder) {
+        matcher_profile.record_stake(bet.amount);
+    } else {
+        sponsor_profile
+            .ok_or(ErrorCode::InvalidFunder)?
+            .record_stake(bet.amount);
+    }
+
     // Update market statistics
-    market.total_settled_count = market.total_settled_count.checked_add(1).unwrap();
+    market.record_settlement(current_time);
 
     // Emit event instead of using msg!
     emit!(BetSettled {
         bet: bet.key(),
-        market: market.key(),
+        market: market_key,
         token_name: market.get_token_name(),
         current_price,
         price_threshold: bet.price_threshold,
         price_direction: bet.price_direction,
+        reference_price: bet.reference_price,
+        other_price,
+        other_reference_price: bet.other_reference_price,
         winner,
+        memo: bet.get_memo(),
     });
 
     msg!(
-        "Bet settled for {}: current price {} vs threshold {}",
+        "Bet settled for {}: current price {} vs {}",
         market.get_token_name(),
         current_price,
-        bet.price_threshold
+        bet.price_direction.describe(bet.price_threshold)
     );
 
     Ok(())
@@ -0,0 +1,81 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::round_price;
+use crate::state::{BettingMarket, BucketPool, MarketLoader, SettlementRound};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct BucketPoolSettled {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub settlement_price: u64,
+    pub winning_bucket: Option<u8>,
+    pub totals: Vec<u64>,
+}
+
+/// Settles a bucket pool on its slot's recorded price. Anyone can call it.
+#[derive(Accounts)]
+pub struct SettleBucketPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bucket_pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &BucketPool::boundaries_seed(&pool.boundaries)
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, BucketPool>,
+
+    #[account(
+        seeds = [b"round", market.key().as_ref(), &pool.settlement_time.to_le_bytes()],
+        bump = settlement_round.bump
+    )]
+    pub settlement_round: Account<'info, SettlementRound>,
+}
+
+pub fn settle_bucket_pool(ctx: Context<SettleBucketPool>) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.is_settled, ErrorCode::PoolAlreadySettled);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= pool.settlement_time,
+        ErrorCode::SettlementTimeTooEarly
+    );
+
+    let price = round_price(
+        &ctx.accounts.settlement_round,
+        &ctx.accounts.market.key(),
+        &market,
+        current_time,
+    )?;
+    pool.settle(price);
+    market.record_settlement(current_time);
+
+    emit!(BucketPoolSettled {
+        pool: pool.key(),
+        market: pool.market,
+        settlement_price: price,
+        winning_bucket: pool.winning_bucket,
+        totals: pool.totals.clone(),
+    });
+
+    msg!(
+        "Bucket pool settled for {}: price {} in bucket {}",
+        market.get_token_name(),
+        price,
+        pool.bucket_for(price)
+    );
+
+    Ok(())
+}
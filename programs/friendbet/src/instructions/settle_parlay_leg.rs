@@ -0,0 +1,144 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::settlement_price;
+use crate::state::{BettingMarket, MarketLoader, Parlay, SettlementRound, UserProfile};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[event]
+pub struct ParlayLegSettled {
+    pub parlay: Pubkey,
+    pub leg_index: u8,
+    pub market: Pubkey,
+    pub settlement_price: u64,
+}
+
+#[event]
+pub struct ParlaySettled {
+    pub parlay: Pubkey,
+    pub leg_prices: Vec<u64>,
+    pub winner: Pubkey,
+}
+
+/// Records the settlement price of one leg. Anyone can call it; the parlay
+/// settles once every leg is priced.
+#[derive(Accounts)]
+#[instruction(leg_index: u8)]
+pub struct SettleParlayLeg<'info> {
+    #[account(
+        mut,
+        seeds = [b"parlay", parlay.better.as_ref(), &parlay.parlay_id.to_le_bytes()],
+        bump = parlay.bump
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = parlay.legs.get(leg_index as usize).map(|leg| leg.market) == Some(market.key()) @ ErrorCode::InvalidParlayLeg
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    /// The Pyth price update account. Not needed when the leg settles on a round.
+    #[account(
+        address = market.load_market()?.oracle_account,
+    )]
+    pub price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    /// The recorded price for the leg's settlement slot, if it settles on one
+    #[account(
+        seeds = [b"round", market.key().as_ref(), &parlay.legs[leg_index as usize].settlement_time.to_le_bytes()],
+        bump = settlement_round.bump
+    )]
+    pub settlement_round: Option<Account<'info, SettlementRound>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", parlay.better.as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", parlay.matcher.unwrap_or_default().as_ref()],
+        bump = matcher_profile.bump
+    )]
+    pub matcher_profile: Account<'info, UserProfile>,
+}
+
+pub fn settle_parlay_leg(ctx: Context<SettleParlayLeg>, leg_index: u8) -> Result<()> {
+    let parlay = &mut ctx.accounts.parlay;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    require!(parlay.is_matched, ErrorCode::BetNotMatched);
+
+    let leg = parlay.legs[leg_index as usize];
+    require!(
+        parlay.leg_prices[leg_index as usize].is_none(),
+        ErrorCode::ParlayLegAlreadySettled
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= leg.settlement_time,
+        ErrorCode::SettlementTimeTooEarly
+    );
+
+    let price = settlement_price(
+        &ctx.accounts.market.key(),
+        &market,
+        parlay.legs_on_round[leg_index as usize],
+        ctx.accounts.settlement_round.as_deref(),
+        ctx.accounts.price_update.as_deref(),
+        current_time,
+    )?;
+    parlay.leg_prices[leg_index as usize] = Some(price);
+
+    // The leg's market is no longer needed by this parlay
+    market.remove_open_interest(parlay.amount.checked_add(parlay.matcher_amount).unwrap());
+    market.remove_open_bet()?;
+
+    emit!(ParlayLegSettled {
+        parlay: parlay.key(),
+        leg_index,
+        market: leg.market,
+        settlement_price: price,
+    });
+
+    msg!(
+        "Parlay leg {} settled for {}: price {} vs {}",
+        leg_index,
+        market.get_token_name(),
+        price,
+        leg.price_direction.describe(leg.price_threshold)
+    );
+
+    if parlay.all_legs_priced() {
+        let better_won = parlay.better_wins();
+        let winner = if better_won {
+            parlay.better
+        } else {
+            parlay.matcher.unwrap()
+        };
+        parlay.is_settled = true;
+        parlay.winner = Some(winner);
+
+        ctx.accounts
+            .better_profile
+            .record_settlement(better_won, parlay.amount);
+        ctx.accounts
+            .matcher_profile
+            .record_settlement(!better_won, parlay.matcher_amount);
+
+        emit!(ParlaySettled {
+            parlay: parlay.key(),
+            leg_prices: parlay.leg_prices.iter().flatten().copied().collect(),
+            winner,
+        });
+
+        msg!("Parlay settled: {} wins", winner);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::round_price;
+use crate::state::{BettingMarket, MarketLoader, Pool, PoolSide, SettlementRound};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PoolSettled {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub settlement_price: u64,
+    pub winning_side: Option<PoolSide>,
+    pub yes_total: u64,
+    pub no_total: u64,
+}
+
+/// Settles a pool on its slot's recorded price. Anyone can call it.
+#[derive(Accounts)]
+pub struct SettlePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &pool.price_threshold.to_le_bytes(),
+            &pool.price_direction.seed()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"round", market.key().as_ref(), &pool.settlement_time.to_le_bytes()],
+        bump = settlement_round.bump
+    )]
+    pub settlement_round: Account<'info, SettlementRound>,
+}
+
+pub fn settle_pool(ctx: Context<SettlePool>) -> Result<()> {
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.is_settled, ErrorCode::PoolAlreadySettled);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= pool.settlement_time,
+        ErrorCode::SettlementTimeTooEarly
+    );
+
+    let price = round_price(
+        &ctx.accounts.settlement_round,
+        &ctx.accounts.market.key(),
+        &market,
+        current_time,
+    )?;
+    pool.settle(price);
+    market.record_settlement(current_time);
+
+    emit!(PoolSettled {
+        pool: pool.key(),
+        market: pool.market,
+        settlement_price: price,
+        winning_side: pool.winning_side,
+        yes_total: pool.yes_total,
+        no_total: pool.no_total,
+    });
+
+    msg!(
+        "Pool settled for {}: price {} vs {}",
+        market.get_token_name(),
+        price,
+        pool.price_direction.describe(pool.price_threshold)
+    );
+
+    Ok(())
+}
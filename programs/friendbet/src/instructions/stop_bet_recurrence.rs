@@ -0,0 +1,43 @@
+use crate::errors::ErrorCode;
+use crate::state::Bet;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct BetRecurrenceStopped {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub stopped_by: Pubkey,
+}
+
+/// Opts out of a recurring bet. Either party can stop it any time before
+/// the next round is rolled over; the current round still settles as usual.
+#[derive(Accounts)]
+pub struct StopBetRecurrence<'info> {
+    pub party: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", bet.market.as_ref(), &bet.bet_count.to_le_bytes()],
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration,
+        constraint = bet.better == party.key() || bet.matcher == Some(party.key()) @ ErrorCode::NotBetParty
+    )]
+    pub bet: Account<'info, Bet>,
+}
+
+pub fn stop_bet_recurrence(ctx: Context<StopBetRecurrence>) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+    require!(bet.recurs(), ErrorCode::BetDoesNotRecur);
+
+    bet.remaining_rounds = 0;
+
+    emit!(BetRecurrenceStopped {
+        bet: bet.key(),
+        market: bet.market,
+        stopped_by: ctx.accounts.party.key(),
+    });
+
+    msg!("Recurring bet stopped by {}", ctx.accounts.party.key());
+
+    Ok(())
+}
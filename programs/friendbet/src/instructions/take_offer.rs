@@ -0,0 +1,223 @@
+use crate::errors::ErrorCode;
+use crate::instructions::match_bet::enforce_match_filter;
+use crate::state::{
+    Bet, BettingMarket, MarketLoader, MatchFilter, Offer, UserBetIndex, UserProfile,
+};
+use crate::USDC_MINT;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use std::str::FromStr;
+
+#[event]
+pub struct OfferTaken {
+    pub offer: Pubkey,
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub taker: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+/// Takes part of an offer, opening a matched bet between the creator and
+/// the taker. The creator's stake comes out of the offer vault.
+#[derive(Accounts)]
+pub struct TakeOffer<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offer", market.key().as_ref(), offer.creator.as_ref(), &offer.offer_id.to_le_bytes()],
+        bump = offer.bump
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == offer.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        init,
+        payer = taker,
+        space = Bet::LEN,
+        seeds = [b"bet", market.key().as_ref(), &(market.load_market()?.bet_count + 1).to_le_bytes()],
+        bump
+    )]
+    pub bet: Box<Account<'info, Bet>>,
+
+    #[account(
+        constraint = usdc_mint.key() == Pubkey::from_str(USDC_MINT).unwrap()
+    )]
+    pub usdc_mint: Box<Account<'info, token::Mint>>,
+
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = bet,
+    )]
+    pub bet_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = offer_vault.key() == offer.vault
+    )]
+    pub offer_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = taker_token_account.mint == usdc_mint.key(),
+        constraint = taker_token_account.owner == taker.key()
+    )]
+    pub taker_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Required when the market has a match filter configured
+    #[account(
+        seeds = [b"match_filter", market.key().as_ref()],
+        bump = match_filter.bump
+    )]
+    pub match_filter: Option<Box<Account<'info, MatchFilter>>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", offer.creator.as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(
+        init,
+        payer = taker,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", offer.creator.as_ref(), &creator_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub creator_bet_index: Box<Account<'info, UserBetIndex>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", taker.key().as_ref()],
+        bump = taker_profile.bump
+    )]
+    pub taker_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(
+        init,
+        payer = taker,
+        space = UserBetIndex::LEN,
+        seeds = [b"bet_index", taker.key().as_ref(), &taker_profile.bet_index_count.to_le_bytes()],
+        bump
+    )]
+    pub taker_bet_index: Box<Account<'info, UserBetIndex>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn take_offer(ctx: Context<TakeOffer>, amount: u64) -> Result<()> {
+    let offer = &mut ctx.accounts.offer;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    market.require_active()?;
+    market.check_stake(amount)?;
+
+    let taker = ctx.accounts.taker.key();
+    require!(taker != offer.creator, ErrorCode::SelfMatch);
+
+    enforce_match_filter(&market, ctx.accounts.match_filter.as_deref(), &taker)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(current_time < offer.settlement_time, ErrorCode::BetExpired);
+
+    let new_bet = offer.take(taker, amount)?;
+
+    // Move the creator's stake out of the vault
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"offer".as_ref(),
+        offer.market.as_ref(),
+        offer.creator.as_ref(),
+        &offer.offer_id.to_le_bytes(),
+        &[offer.bump],
+    ]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.offer_vault.to_account_info(),
+                to: ctx.accounts.bet_escrow.to_account_info(),
+                authority: offer.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    // Transfer the taker's matching stake
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.taker_token_account.to_account_info(),
+        to: ctx.accounts.bet_escrow.to_account_info(),
+        authority: ctx.accounts.taker.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let bet_count = market.bet_count + 1;
+    let bet = &mut ctx.accounts.bet;
+    bet.set_inner(Bet {
+        escrow: ctx.accounts.bet_escrow.key(),
+        bet_count,
+        matched_at: current_time,
+        settles_on_round: market.is_settlement_slot(new_bet.settlement_time),
+        bump: ctx.bumps.bet,
+        ..new_bet
+    });
+
+    // Update market stats for both stakes. The creator's was already counted
+    // as open interest when the offer was funded.
+    market.bet_count = bet_count;
+    market.add_open_bet();
+    market.record_volume(current_time, amount.checked_mul(2).unwrap());
+    market.add_open_interest(amount)?;
+    market.record_match(current_time);
+
+    // Record the bet in both parties' profiles and bet indexes
+    let creator_profile = &mut ctx.accounts.creator_profile;
+    creator_profile.bets_created = creator_profile.bets_created.checked_add(1).unwrap();
+    let creator_bet_index = &mut ctx.accounts.creator_bet_index;
+    creator_bet_index.owner = creator_profile.owner;
+    creator_bet_index.index = creator_profile.next_bet_index();
+    creator_bet_index.bet = bet.key();
+    creator_bet_index.bump = ctx.bumps.creator_bet_index;
+
+    let taker_profile = &mut ctx.accounts.taker_profile;
+    taker_profile.bets_matched = taker_profile.bets_matched.checked_add(1).unwrap();
+    let taker_bet_index = &mut ctx.accounts.taker_bet_index;
+    taker_bet_index.owner = taker_profile.owner;
+    taker_bet_index.index = taker_profile.next_bet_index();
+    taker_bet_index.bet = bet.key();
+    taker_bet_index.bump = ctx.bumps.taker_bet_index;
+
+    emit!(OfferTaken {
+        offer: offer.key(),
+        bet: bet.key(),
+        market: offer.market,
+        creator: offer.creator,
+        taker,
+        amount,
+        remaining: offer.remaining,
+    });
+
+    msg!(
+        "Offer taken by {}: {} USDC on {} ({} USDC left)",
+        taker,
+        amount / 1_000_000,
+        market.get_token_name(),
+        offer.remaining / 1_000_000
+    );
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::{normalize_price, settle_at_price, verified_price};
+use crate::state::{Bet, BettingMarket, MarketLoader, UserProfile};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+/// Settles a touch bet early for the better. Anyone can call it with a price
+/// update showing the barrier was crossed while the bet was live.
+#[derive(Accounts)]
+pub struct TriggerBarrier<'info> {
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration
+    )]
+    pub bet: Account<'info, Bet>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == bet.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    /// Any fully verified Pyth update for the market's feed published while
+    /// the bet was live, typically a historical update posted for the crossing
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.better.as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.matcher.unwrap_or_default().as_ref()],
+        bump = matcher_profile.bump
+    )]
+    pub matcher_profile: Account<'info, UserProfile>,
+
+    /// Profile of a sponsor who funded the better's stake, required when there is one
+    #[account(
+        mut,
+        seeds = [b"profile", bet.funder.as_ref()],
+        bump = sponsor_profile.bump,
+        constraint = bet.sponsor().is_some() @ ErrorCode::InvalidFunder
+    )]
+    pub sponsor_profile: Option<Account<'info, UserProfile>>,
+}
+
+pub fn trigger_barrier(ctx: Context<TriggerBarrier>) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    require!(bet.price_direction.is_touch(), ErrorCode::NotTouchBet);
+    require!(bet.is_matched, ErrorCode::BetNotMatched);
+    require!(!bet.is_settled, ErrorCode::BetAlreadySettled);
+
+    let pyth_price = verified_price(&ctx.accounts.price_update, &market)?;
+    require!(
+        pyth_price.publish_time >= bet.matched_at && pyth_price.publish_time <= bet.settlement_time,
+        ErrorCode::PriceOutsideBetWindow
+    );
+
+    let price = normalize_price(&pyth_price)?;
+    require!(bet.better_wins(price, 0), ErrorCode::BarrierNotCrossed);
+
+    settle_at_price(
+        bet,
+        ctx.accounts.market.key(),
+        &mut market,
+        &mut ctx.accounts.better_profile,
+        &mut ctx.accounts.matcher_profile,
+        ctx.accounts.sponsor_profile.as_mut(),
+        price,
+        0,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    Ok(())
+}
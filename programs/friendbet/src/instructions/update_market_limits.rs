@@ -0,0 +1,47 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMarketLimits<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+}
+
+pub fn update_market_limits(
+    ctx: Context<UpdateMarketLimits>,
+    min_stake: u64,
+    max_stake: u64,
+    max_open_interest: u64,
+) -> Result<()> {
+    // Zero means "no limit" for the maximums
+    require!(
+        max_stake == 0 || max_stake >= min_stake,
+        ErrorCode::InvalidMarketLimits
+    );
+    require!(
+        max_open_interest == 0 || max_open_interest >= max_stake,
+        ErrorCode::InvalidMarketLimits
+    );
+
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    market.min_stake = min_stake;
+    market.max_stake = max_stake;
+    market.max_open_interest = max_open_interest;
+
+    msg!(
+        "Limits updated for {}: stake {}-{}, max open interest {}",
+        market.get_token_name(),
+        min_stake,
+        max_stake,
+        max_open_interest
+    );
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, FilterMode, MarketLoader, MatchFilter, MAX_FILTER_WALLETS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMatchFilter<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"match_filter", market.key().as_ref()],
+        bump = match_filter.bump,
+        constraint = match_filter.market == market.key()
+    )]
+    pub match_filter: Account<'info, MatchFilter>,
+}
+
+pub fn update_match_filter(
+    ctx: Context<UpdateMatchFilter>,
+    mode: FilterMode,
+    wallets: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        wallets.len() <= MAX_FILTER_WALLETS,
+        ErrorCode::TooManyFilterWallets
+    );
+
+    let match_filter = &mut ctx.accounts.match_filter;
+    match_filter.mode = mode;
+    match_filter.wallets = wallets;
+
+    msg!(
+        "Match filter updated for {} with {} wallets",
+        ctx.accounts.market.load_market()?.get_token_name(),
+        match_filter.wallets.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, PriceSource};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePriceSource<'info> {
+    pub authority: Signer<'info>,
+
+    /// Open bets, pools, parlays and offers were priced under the current
+    /// settings, so they can only change once none are left
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.load_market()?.open_bets_untracked == 0 @ ErrorCode::OpenBetCountNotBackfilled,
+        constraint = market.load_market()?.open_bet_count == 0 @ ErrorCode::MarketHasOpenBets
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+}
+
+pub fn update_price_source(
+    ctx: Context<UpdatePriceSource>,
+    price_source: PriceSource,
+    twap_window: u32,
+    twap_min_samples: u32,
+) -> Result<()> {
+    // A window with no samples (or samples with no window) can never complete
+    require!(
+        (twap_window == 0) == (twap_min_samples == 0),
+        ErrorCode::InvalidTwapSettings
+    );
+
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    market.set_price_source(price_source);
+    market.twap_window = twap_window;
+    market.twap_min_samples = twap_min_samples;
+
+    msg!(
+        "Price source updated for {}: {}, TWAP window {}s with {} samples",
+        market.get_token_name(),
+        match price_source {
+            PriceSource::Spot => "spot",
+            PriceSource::Ema => "EMA",
+        },
+        twap_window,
+        twap_min_samples
+    );
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateSettlementBounds<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+}
+
+pub fn update_settlement_bounds(
+    ctx: Context<UpdateSettlementBounds>,
+    min_duration: u32,
+    max_duration: u32,
+    settlement_interval: u32,
+    settlement_offset: u32,
+) -> Result<()> {
+    // Zero falls back to the program defaults, so only check explicit pairs
+    require!(
+        min_duration == 0 || max_duration == 0 || max_duration >= min_duration,
+        ErrorCode::InvalidSettlementBounds
+    );
+    // The offset positions slots within the interval, e.g. 16:00 UTC on a daily schedule
+    require!(
+        settlement_offset == 0 || settlement_offset < settlement_interval,
+        ErrorCode::InvalidSettlementBounds
+    );
+
+    let mut market = ctx.accounts.market.load_market_mut()?;
+    market.min_duration = min_duration;
+    market.max_duration = max_duration;
+    market.settlement_interval = settlement_interval;
+    market.settlement_offset = settlement_offset;
+
+    msg!(
+        "Settlement bounds updated for {}: {}s-{}s, interval {}s, offset {}s",
+        market.get_token_name(),
+        min_duration,
+        max_duration,
+        settlement_interval,
+        settlement_offset
+    );
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::errors::ErrorCode;
+use crate::instructions::settle_bet::load_other_market;
+use crate::state::{Bet, BettingMarket, MarketLoader, UserProfile, VOID_DELAY};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[event]
+pub struct BetVoided {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub better: Pubkey,
+    pub matcher: Pubkey,
+    pub refund_recipient: Pubkey,
+    pub refund_amount: u64,
+}
+
+/// Voids a matched bet that could not be settled (e.g. no usable price for
+/// its settlement time), returning each side's stake to whoever put it up.
+#[derive(Accounts)]
+pub struct VoidBet<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: The account of the original bettor, which receives the bet's rent.
+    #[account(mut)]
+    pub better: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), &bet.bet_count.to_le_bytes()],
+        bump = bet.bump,
+        constraint = bet.version == Bet::VERSION @ ErrorCode::AccountNeedsMigration,
+        constraint = bet.better == better.key() @ ErrorCode::InvalidBetter,
+        constraint = bet.is_matched @ ErrorCode::BetNotMatched,
+        constraint = !bet.is_settled @ ErrorCode::BetAlreadySettled,
+        close = better
+    )]
+    pub bet: Account<'info, Bet>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == bet.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        constraint = bet_escrow.key() == bet.escrow
+    )]
+    pub bet_escrow: Account<'info, TokenAccount>,
+
+    /// Receives the better's stake. Must belong to whoever funded the bet.
+    #[account(
+        mut,
+        constraint = refund_token_account.owner == bet.refund_recipient() @ ErrorCode::InvalidFunder
+    )]
+    pub refund_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = Some(matcher_token_account.owner) == bet.matcher @ ErrorCode::NotBetParty
+    )]
+    pub matcher_token_account: Account<'info, TokenAccount>,
+
+    /// Second market of a relative-performance bet, which counts the bet as open
+    #[account(mut)]
+    pub other_market: Option<AccountLoader<'info, BettingMarket>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.better.as_ref()],
+        bump = better_profile.bump
+    )]
+    pub better_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", bet.matcher.unwrap_or_default().as_ref()],
+        bump = matcher_profile.bump
+    )]
+    pub matcher_profile: Account<'info, UserProfile>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn void_bet(ctx: Context<VoidBet>) -> Result<()> {
+    let bet = &ctx.accounts.bet;
+    let mut market = ctx.accounts.market.load_market_mut()?;
+
+    // Only bets that have gone unsettled well past their settlement time
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= bet.settlement_time + VOID_DELAY,
+        ErrorCode::VoidTooEarly
+    );
+
+    // Create signing authority for the bet PDA
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"bet".as_ref(),
+        bet.market.as_ref(),
+        &bet.bet_count.to_le_bytes(),
+        &[bet.bump],
+    ]];
+
+    // Each side gets back exactly what it staked
+    for to in [
+        &ctx.accounts.refund_token_account,
+        &ctx.accounts.matcher_token_account,
+    ] {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.bet_escrow.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.bet.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            bet.amount,
+        )?;
+    }
+
+    let refund_amount = bet.amount.checked_mul(2).unwrap();
+    market.remove_open_interest(refund_amount);
+    // The bet account is closed once voided
+    market.remove_open_bet()?;
+    if let Some(other_market) = bet.price_direction.other_market() {
+        load_other_market(ctx.accounts.other_market.as_ref(), other_market)?
+            .load_market_mut()?
+            .remove_open_bet()?;
+    }
+
+    ctx.accounts.better_profile.record_push();
+    ctx.accounts.matcher_profile.record_push();
+
+    emit!(BetVoided {
+        bet: bet.key(),
+        market: ctx.accounts.market.key(),
+        better: bet.better,
+        matcher: bet.matcher.unwrap(),
+        refund_recipient: bet.refund_recipient(),
+        refund_amount,
+    });
+
+    msg!(
+        "Bet voided: {} USDC returned to each side of bet on {}",
+        bet.amount / 1_000_000,
+        market.get_token_name()
+    );
+
+    Ok(())
+}
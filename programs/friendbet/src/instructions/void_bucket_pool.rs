@@ -0,0 +1,62 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, BucketPool, MarketLoader, VOID_DELAY};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct BucketPoolVoided {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub totals: Vec<u64>,
+}
+
+/// Bucket pool counterpart of `VoidPool`
+#[derive(Accounts)]
+pub struct VoidBucketPool<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bucket_pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &BucketPool::boundaries_seed(&pool.boundaries)
+        ],
+        bump = pool.bump,
+        constraint = !pool.is_settled @ ErrorCode::PoolAlreadySettled
+    )]
+    pub pool: Account<'info, BucketPool>,
+}
+
+pub fn void_bucket_pool(ctx: Context<VoidBucketPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= pool.settlement_time + VOID_DELAY,
+        ErrorCode::PoolVoidTooEarly
+    );
+
+    pool.void();
+
+    emit!(BucketPoolVoided {
+        pool: pool.key(),
+        market: pool.market,
+        totals: pool.totals.clone(),
+    });
+
+    msg!(
+        "Bucket pool voided on {}: {} USDC to refund",
+        ctx.accounts.market.load_market()?.get_token_name(),
+        pool.total() / 1_000_000
+    );
+
+    Ok(())
+}
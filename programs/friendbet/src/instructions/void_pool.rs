@@ -0,0 +1,66 @@
+use crate::errors::ErrorCode;
+use crate::state::{BettingMarket, MarketLoader, Pool, VOID_DELAY};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PoolVoided {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub yes_total: u64,
+    pub no_total: u64,
+}
+
+/// Voids a pool whose settlement round was never recorded, so every position
+/// can claim its stake back
+#[derive(Accounts)]
+pub struct VoidPool<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.load_market()?.feed_id_seed(), &market.load_market()?.market_index_seed()],
+        bump = market.load_market()?.bump,
+        constraint = market.key() == pool.market
+    )]
+    pub market: AccountLoader<'info, BettingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            market.key().as_ref(),
+            &pool.settlement_time.to_le_bytes(),
+            &pool.price_threshold.to_le_bytes(),
+            &pool.price_direction.seed()
+        ],
+        bump = pool.bump,
+        constraint = !pool.is_settled @ ErrorCode::PoolAlreadySettled
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn void_pool(ctx: Context<VoidPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time >= pool.settlement_time + VOID_DELAY,
+        ErrorCode::PoolVoidTooEarly
+    );
+
+    pool.void();
+
+    emit!(PoolVoided {
+        pool: pool.key(),
+        market: pool.market,
+        yes_total: pool.yes_total,
+        no_total: pool.no_total,
+    });
+
+    msg!(
+        "Pool voided on {}: {} USDC to refund",
+        ctx.accounts.market.load_market()?.get_token_name(),
+        pool.yes_total.checked_add(pool.no_total).unwrap() / 1_000_000
+    );
+
+    Ok(())
+}
@@ -22,21 +22,122 @@ pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 pub mod friendbet {
     use super::*;
 
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>) -> Result<()> {
+        instructions::initialize_registry::initialize_registry(ctx)
+    }
+
     #[access_control(enforce_admin(ctx.accounts.authority.key))]
     pub fn initialize_market(
         ctx: Context<InitializeMarket>,
         token_name: String,
         fee_claimer: Pubkey,
         feed_id_hex: String,
+        market_index: u16,
     ) -> Result<()> {
         instructions::initialize_market::initialize_market(
             ctx,
             token_name,
             fee_claimer,
             feed_id_hex,
+            market_index,
         )
     }
 
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn register_market(ctx: Context<RegisterMarket>) -> Result<()> {
+        instructions::register_market::register_market(ctx)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn deactivate_market(ctx: Context<DeactivateMarket>) -> Result<()> {
+        instructions::deactivate_market::deactivate_market(ctx)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        instructions::close_market::close_market(ctx)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn backfill_open_bet_count(ctx: Context<BackfillOpenBetCount>, count: u64) -> Result<()> {
+        instructions::backfill_open_bet_count::backfill_open_bet_count(ctx, count)
+    }
+
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_account::migrate_account(ctx)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn initialize_match_filter(
+        ctx: Context<InitializeMatchFilter>,
+        mode: FilterMode,
+        wallets: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::initialize_match_filter::initialize_match_filter(ctx, mode, wallets)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn update_match_filter(
+        ctx: Context<UpdateMatchFilter>,
+        mode: FilterMode,
+        wallets: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_match_filter::update_match_filter(ctx, mode, wallets)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn update_market_limits(
+        ctx: Context<UpdateMarketLimits>,
+        min_stake: u64,
+        max_stake: u64,
+        max_open_interest: u64,
+    ) -> Result<()> {
+        instructions::update_market_limits::update_market_limits(
+            ctx,
+            min_stake,
+            max_stake,
+            max_open_interest,
+        )
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn update_settlement_bounds(
+        ctx: Context<UpdateSettlementBounds>,
+        min_duration: u32,
+        max_duration: u32,
+        settlement_interval: u32,
+        settlement_offset: u32,
+    ) -> Result<()> {
+        instructions::update_settlement_bounds::update_settlement_bounds(
+            ctx,
+            min_duration,
+            max_duration,
+            settlement_interval,
+            settlement_offset,
+        )
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn update_price_source(
+        ctx: Context<UpdatePriceSource>,
+        price_source: PriceSource,
+        twap_window: u32,
+        twap_min_samples: u32,
+    ) -> Result<()> {
+        instructions::update_price_source::update_price_source(
+            ctx,
+            price_source,
+            twap_window,
+            twap_min_samples,
+        )
+    }
+
+    pub fn initialize_profile(ctx: Context<InitializeProfile>, owner: Pubkey) -> Result<()> {
+        instructions::initialize_profile::initialize_profile(ctx, owner)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     #[access_control(enforce_admin(ctx.accounts.admin.key))]
     pub fn create_bet_for_user(
         ctx: Context<CreateBetForUser>,
@@ -46,6 +147,8 @@ pub mod friendbet {
         settlement_time: i64,
         better_pubkey: Pubkey,
         fund_immediately: bool,
+        gift_winnings: bool,
+        memo: String,
     ) -> Result<()> {
         instructions::create_bet_for_user::create_bet_for_user(
             ctx,
@@ -55,6 +158,8 @@ pub mod friendbet {
             settlement_time,
             better_pubkey,
             fund_immediately,
+            gift_winnings,
+            memo,
         )
     }
 
@@ -64,6 +169,7 @@ pub mod friendbet {
         price_threshold: u64,
         price_direction: PriceDirection,
         settlement_time: i64,
+        memo: String,
     ) -> Result<()> {
         instructions::create_bet::create_bet(
             ctx,
@@ -71,21 +177,181 @@ pub mod friendbet {
             price_threshold,
             price_direction,
             settlement_time,
+            memo,
+        )
+    }
+
+    pub fn fund_bet(ctx: Context<FundBet>, gift_winnings: bool) -> Result<()> {
+        instructions::fund_bet::fund_bet(ctx, gift_winnings)
+    }
+
+    pub fn match_bet(
+        ctx: Context<MatchBet>,
+        expected_recurrence_interval: i64,
+        expected_remaining_rounds: u16,
+    ) -> Result<()> {
+        instructions::match_bet::match_bet(
+            ctx,
+            expected_recurrence_interval,
+            expected_remaining_rounds,
         )
     }
 
-    pub fn fund_bet(ctx: Context<FundBet>) -> Result<()> {
-        instructions::fund_bet::fund_bet(ctx)
+    pub fn record_settlement_round(
+        ctx: Context<RecordSettlementRound>,
+        settlement_time: i64,
+    ) -> Result<()> {
+        instructions::record_settlement_round::record_settlement_round(ctx, settlement_time)
     }
 
-    pub fn match_bet(ctx: Context<MatchBet>) -> Result<()> {
-        instructions::match_bet::match_bet(ctx)
+    pub fn add_settlement_sample(ctx: Context<AddSettlementSample>) -> Result<()> {
+        instructions::add_settlement_sample::add_settlement_sample(ctx)
     }
 
     pub fn settle_bet(ctx: Context<SettleBet>) -> Result<()> {
         instructions::settle_bet::settle_bet(ctx)
     }
 
+    pub fn trigger_barrier(ctx: Context<TriggerBarrier>) -> Result<()> {
+        instructions::trigger_barrier::trigger_barrier(ctx)
+    }
+
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+        settlement_time: i64,
+        price_threshold: u64,
+        price_direction: PriceDirection,
+    ) -> Result<()> {
+        instructions::create_pool::create_pool(
+            ctx,
+            settlement_time,
+            price_threshold,
+            price_direction,
+        )
+    }
+
+    pub fn enter_pool(ctx: Context<EnterPool>, side: PoolSide, amount: u64) -> Result<()> {
+        instructions::enter_pool::enter_pool(ctx, side, amount)
+    }
+
+    pub fn settle_pool(ctx: Context<SettlePool>) -> Result<()> {
+        instructions::settle_pool::settle_pool(ctx)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn void_pool(ctx: Context<VoidPool>) -> Result<()> {
+        instructions::void_pool::void_pool(ctx)
+    }
+
+    pub fn claim_pool(ctx: Context<ClaimPool>) -> Result<()> {
+        instructions::claim_pool::claim_pool(ctx)
+    }
+
+    pub fn create_bucket_pool(
+        ctx: Context<CreateBucketPool>,
+        settlement_time: i64,
+        boundaries: Vec<u64>,
+    ) -> Result<()> {
+        instructions::create_bucket_pool::create_bucket_pool(ctx, settlement_time, boundaries)
+    }
+
+    pub fn enter_bucket_pool(ctx: Context<EnterBucketPool>, bucket: u8, amount: u64) -> Result<()> {
+        instructions::enter_bucket_pool::enter_bucket_pool(ctx, bucket, amount)
+    }
+
+    pub fn settle_bucket_pool(ctx: Context<SettleBucketPool>) -> Result<()> {
+        instructions::settle_bucket_pool::settle_bucket_pool(ctx)
+    }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn void_bucket_pool(ctx: Context<VoidBucketPool>) -> Result<()> {
+        instructions::void_bucket_pool::void_bucket_pool(ctx)
+    }
+
+    pub fn claim_bucket_pool(ctx: Context<ClaimBucketPool>) -> Result<()> {
+        instructions::claim_bucket_pool::claim_bucket_pool(ctx)
+    }
+
+    pub fn create_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateParlay<'info>>,
+        parlay_id: u64,
+        amount: u64,
+        matcher_amount: u64,
+        legs: Vec<ParlayLeg>,
+    ) -> Result<()> {
+        instructions::create_parlay::create_parlay(ctx, parlay_id, amount, matcher_amount, legs)
+    }
+
+    pub fn match_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchParlay<'info>>,
+    ) -> Result<()> {
+        instructions::match_parlay::match_parlay(ctx)
+    }
+
+    pub fn settle_parlay_leg(ctx: Context<SettleParlayLeg>, leg_index: u8) -> Result<()> {
+        instructions::settle_parlay_leg::settle_parlay_leg(ctx, leg_index)
+    }
+
+    pub fn claim_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimParlay<'info>>,
+    ) -> Result<()> {
+        instructions::claim_parlay::claim_parlay(ctx)
+    }
+
+    pub fn cancel_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelParlay<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_parlay::cancel_parlay(ctx)
+    }
+
+    pub fn set_bet_recurrence(
+        ctx: Context<SetBetRecurrence>,
+        recurrence_interval: i64,
+        remaining_rounds: u16,
+    ) -> Result<()> {
+        instructions::set_bet_recurrence::set_bet_recurrence(
+            ctx,
+            recurrence_interval,
+            remaining_rounds,
+        )
+    }
+
+    pub fn stop_bet_recurrence(ctx: Context<StopBetRecurrence>) -> Result<()> {
+        instructions::stop_bet_recurrence::stop_bet_recurrence(ctx)
+    }
+
+    pub fn roll_over_bet(ctx: Context<RollOverBet>) -> Result<()> {
+        instructions::roll_over_bet::roll_over_bet(ctx)
+    }
+
+    pub fn create_offer(
+        ctx: Context<CreateOffer>,
+        offer_id: u64,
+        capacity: u64,
+        price_threshold: u64,
+        price_direction: PriceDirection,
+        settlement_time: i64,
+        memo: String,
+    ) -> Result<()> {
+        instructions::create_offer::create_offer(
+            ctx,
+            offer_id,
+            capacity,
+            price_threshold,
+            price_direction,
+            settlement_time,
+            memo,
+        )
+    }
+
+    pub fn take_offer(ctx: Context<TakeOffer>, amount: u64) -> Result<()> {
+        instructions::take_offer::take_offer(ctx, amount)
+    }
+
+    pub fn close_offer(ctx: Context<CloseOffer>) -> Result<()> {
+        instructions::close_offer::close_offer(ctx)
+    }
+
     pub fn claim_funds(ctx: Context<ClaimFunds>) -> Result<()> {
         instructions::claim_funds::claim_funds(ctx)
     }
@@ -93,6 +359,11 @@ pub mod friendbet {
     pub fn close_bet(ctx: Context<CloseBet>) -> Result<()> {
         instructions::close_bet::close_bet(ctx)
     }
+
+    #[access_control(enforce_admin(ctx.accounts.authority.key))]
+    pub fn void_bet(ctx: Context<VoidBet>) -> Result<()> {
+        instructions::void_bet::void_bet(ctx)
+    }
 }
 
 fn enforce_admin(key: &Pubkey) -> Result<()> {
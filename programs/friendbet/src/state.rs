@@ -1,54 +1,151 @@
+use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
 
-#[account]
+/// Market account, stored zero-copy so it can carry a large reserved region
+/// and stats history without deserializing the whole account on every call.
+/// Fields are grouped by alignment so the `repr(C)` layout has no padding.
+#[account(zero_copy)]
+#[repr(C)]
 pub struct BettingMarket {
-    pub bump: u8,
+    pub bet_count: u64,
+    pub total_volume: u64,        // Total USDC staked on matched bets and pools
+    pub total_matched_count: u64, // Total number of bets that were matched
+    pub total_settled_count: u64, // Total number of bets that were settled
+    pub total_fees_collected: u64, // Total fees collected in USDC
+    pub min_stake: u64,           // Minimum stake per side in USDC (0 = MIN_STAKE)
+    pub max_stake: u64,           // Maximum stake per side in USDC (0 = no limit)
+    pub max_open_interest: u64,   // Cap on USDC held in this market's escrows (0 = no limit)
+    pub open_interest: u64,       // USDC currently held in this market's escrows
+    pub open_bet_count: u64,      // Bet accounts on this market that haven't been closed yet
+    pub daily_stats: [DailyStats; STATS_HISTORY_DAYS], // Ring buffer indexed by day number
+    pub min_duration: u32,        // Min seconds until settlement (0 = default)
+    pub max_duration: u32,        // Max seconds until settlement (0 = default)
+    pub settlement_interval: u32, // Settlement slot length in seconds (0 = any)
+    pub settlement_offset: u32,   // Slot start relative to the unix epoch (e.g. 0 = 00:00 UTC)
+    pub market_index: u16,        // Distinguishes markets on the same feed
     pub authority: Pubkey,
-    pub fee_claimer: Pubkey,    // Added: The account that can claim fees
+    pub fee_claimer: Pubkey,    // The account that can claim fees
     pub token_name: [u8; 40],   // Name of the asset (BTC, ETH, etc.) - fixed 40 bytes
     pub feed_id: [u8; 32],      // Pyth price feed ID as 32-byte array
     pub oracle_account: Pubkey, // Pyth account
-    pub bet_count: u64,
-    pub total_volume: u64,         // Total volume of USDC ever bet
-    pub total_matched_count: u64,  // Total number of bets that were matched
-    pub total_settled_count: u64,  // Total number of bets that were settled
-    pub total_fees_collected: u64, // Total fees collected in USDC
-    pub _reserved: [u64; 10],
+    pub match_filter: Pubkey,   // MatchFilter PDA enforced on match_bet (default = none)
+    pub bump: u8,
+    pub has_market_index: u8, // 0 = legacy 8-byte feed seeds, market_index unused
+    pub status: u8,           // MarketStatus
+    pub version: u8,
+    pub price_source: u8,        // PriceSource used for settlement prices
+    pub open_bets_untracked: u8, // 1 until open_bet_count is backfilled on markets migrated from before it was tracked
+    pub twap_window: u32, // Seconds after a slot to average round samples over (0 = single price)
+    pub twap_min_samples: u32, // Samples a round needs before bets can settle on it
+    pub _reserved: [u64; 63],
 }
 
-impl Default for BettingMarket {
-    fn default() -> Self {
-        Self {
-            authority: Pubkey::default(),
-            fee_claimer: Pubkey::default(),
-            token_name: [0u8; 40],
-            feed_id: [0u8; 32],
-            oracle_account: Pubkey::default(),
-            bet_count: 0,
-            total_volume: 0,
-            total_matched_count: 0,
-            total_settled_count: 0,
-            total_fees_collected: 0,
-            bump: 0,
-            _reserved: [0u64; 10],
-        }
-    }
+/// One day of market activity in the stats ring buffer
+#[zero_copy]
+#[repr(C)]
+#[derive(Default)]
+pub struct DailyStats {
+    pub day: i64, // Days since the unix epoch
+    pub volume: u64,
+    pub fees: u64,
+    pub matched_count: u64,
+    pub settled_count: u64,
 }
 
 impl BettingMarket {
-    pub const LEN: usize = 8 + // discriminator
-        1 + // bump
-        32 +               // authority
-        32 +               // fee_claimer
-        40 +               // token_name (fixed 40 bytes)
-        32 +               // feed_id (32 bytes)
-        32 +               // pyth account (32 bytes)
-        8 +                // bet_count
-        8 +                // total_volume
-        8 +                // total_matched_count
-        8 +                // total_settled_count
-        8 +                // total_fees_collected
-        80; // _reserved (10 u64s)
+    pub const LEN: usize = 8 + std::mem::size_of::<BettingMarket>(); // discriminator + fields
+
+    /// Current layout version
+    pub const VERSION: u8 = 2;
+
+    /// Copy a market out of the Borsh layout used before v2
+    pub fn migrate_from(&mut self, legacy: &LegacyBettingMarket) {
+        self.bet_count = legacy.bet_count;
+        self.total_volume = legacy.total_volume;
+        self.total_matched_count = legacy.total_matched_count;
+        self.total_settled_count = legacy.total_settled_count;
+        self.total_fees_collected = legacy.total_fees_collected;
+        self.min_stake = legacy.min_stake;
+        self.max_stake = legacy.max_stake;
+        self.max_open_interest = legacy.max_open_interest;
+        self.open_interest = legacy.open_interest;
+        self.open_bet_count = legacy.open_bet_count;
+        self.min_duration = legacy.min_duration;
+        self.max_duration = legacy.max_duration;
+        self.settlement_interval = legacy.settlement_interval;
+        self.settlement_offset = legacy.settlement_offset;
+        self.set_market_index(legacy.market_index);
+        self.authority = legacy.authority;
+        self.fee_claimer = legacy.fee_claimer;
+        self.token_name = legacy.token_name;
+        self.feed_id = legacy.feed_id;
+        self.oracle_account = legacy.oracle_account;
+        self.match_filter = legacy.match_filter;
+        self.bump = legacy.bump;
+        self.set_status(legacy.status);
+        // Legacy markets may have been zero-extended to gain open_bet_count
+        // while bets were already open, so it can't be trusted until backfilled
+        self.open_bets_untracked = 1;
+        self.version = Self::VERSION;
+    }
+
+    /// Index distinguishing markets on the same feed. None for markets created
+    /// before full-width seeds.
+    pub fn market_index(&self) -> Option<u16> {
+        (self.has_market_index != 0).then_some(self.market_index)
+    }
+
+    pub fn set_market_index(&mut self, market_index: Option<u16>) {
+        self.has_market_index = market_index.is_some() as u8;
+        self.market_index = market_index.unwrap_or_default();
+    }
+
+    pub fn status(&self) -> MarketStatus {
+        match self.status {
+            0 => MarketStatus::Active,
+            1 => MarketStatus::CloseOnly,
+            _ => MarketStatus::Closed,
+        }
+    }
+
+    pub fn set_status(&mut self, status: MarketStatus) {
+        self.status = status as u8;
+    }
+
+    pub fn price_source(&self) -> PriceSource {
+        match self.price_source {
+            0 => PriceSource::Spot,
+            _ => PriceSource::Ema,
+        }
+    }
+
+    pub fn set_price_source(&mut self, price_source: PriceSource) {
+        self.price_source = price_source as u8;
+    }
+
+    /// Minimum gap between TWAP samples, so a burst of updates during a wick
+    /// can't dominate the average
+    pub fn twap_sample_spacing(&self) -> i64 {
+        (self.twap_window / self.twap_min_samples.max(1)) as i64
+    }
+
+    /// Feed id seed for the market PDA. Markets created before full-width seeds
+    /// were derived from the first 8 bytes of the feed id only.
+    pub fn feed_id_seed(&self) -> &[u8] {
+        match self.market_index() {
+            Some(_) => &self.feed_id,
+            None => &self.feed_id[..8],
+        }
+    }
+
+    /// Market index seed for the market PDA. Empty for legacy markets, which
+    /// derives the same address as their original two-seed derivation.
+    pub fn market_index_seed(&self) -> Vec<u8> {
+        self.market_index()
+            .map(|index| index.to_le_bytes().to_vec())
+            .unwrap_or_default()
+    }
 
     /// Set token name from a string, truncating if necessary
     pub fn set_token_name(&mut self, name: &str) {
@@ -61,6 +158,139 @@ impl BettingMarket {
         }
     }
 
+    /// Ensure the market still accepts new bets and matches
+    pub fn require_active(&self) -> Result<()> {
+        require!(
+            self.status() == MarketStatus::Active,
+            ErrorCode::MarketNotActive
+        );
+        Ok(())
+    }
+    /// Check a stake against the market's min/max stake limits
+    pub fn check_stake(&self, amount: u64) -> Result<()> {
+        let min_stake = if self.min_stake == 0 {
+            MIN_STAKE
+        } else {
+            self.min_stake
+        };
+        require!(amount >= min_stake, ErrorCode::StakeTooSmall);
+        require!(
+            self.max_stake == 0 || amount <= self.max_stake,
+            ErrorCode::StakeTooLarge
+        );
+        Ok(())
+    }
+
+    /// Add a deposit to open interest, enforcing the market's cap
+    pub fn add_open_interest(&mut self, amount: u64) -> Result<()> {
+        let open_interest = self.open_interest.checked_add(amount).unwrap();
+        require!(
+            self.max_open_interest == 0 || open_interest <= self.max_open_interest,
+            ErrorCode::OpenInterestLimitExceeded
+        );
+        self.open_interest = open_interest;
+        Ok(())
+    }
+
+    /// Remove funds paid out of an escrow from open interest
+    pub fn remove_open_interest(&mut self, amount: u64) {
+        // Saturating so escrows funded before open interest was tracked can still be paid out
+        self.open_interest = self.open_interest.saturating_sub(amount);
+    }
+
+    /// Count a newly opened bet, pool position, parlay leg or offer
+    pub fn add_open_bet(&mut self) {
+        self.open_bet_count = self.open_bet_count.checked_add(1).unwrap();
+    }
+
+    /// Release one open bet. Until an untracked count has been backfilled it
+    /// may be missing bets opened before migration, so it stops at zero.
+    pub fn remove_open_bet(&mut self) -> Result<()> {
+        self.open_bet_count = if self.open_bets_untracked != 0 {
+            self.open_bet_count.saturating_sub(1)
+        } else {
+            self.open_bet_count
+                .checked_sub(1)
+                .ok_or(ErrorCode::InvalidOpenBetCount)?
+        };
+        Ok(())
+    }
+
+    /// Check a requested settlement time against the market's horizon and slot rules
+    pub fn check_settlement_time(&self, settlement_time: i64, current_time: i64) -> Result<()> {
+        require!(
+            settlement_time > current_time,
+            ErrorCode::InvalidSettlementTime
+        );
+
+        let min_duration = match self.min_duration {
+            0 => DEFAULT_MIN_DURATION,
+            min_duration => min_duration as i64,
+        };
+        let max_duration = match self.max_duration {
+            0 => DEFAULT_MAX_DURATION,
+            max_duration => max_duration as i64,
+        };
+        require!(
+            settlement_time - current_time >= min_duration,
+            ErrorCode::SettlementTimeTooClose
+        );
+        require!(
+            settlement_time - current_time <= max_duration,
+            ErrorCode::SettlementTimeTooFar
+        );
+        require!(
+            self.settlement_interval == 0 || self.is_settlement_slot(settlement_time),
+            ErrorCode::SettlementTimeNotOnSlot
+        );
+        Ok(())
+    }
+
+    /// Whether the market has a settlement schedule and `time` falls on one of its slots
+    pub fn is_settlement_slot(&self, time: i64) -> bool {
+        self.settlement_interval != 0
+            && (time - self.settlement_offset as i64).rem_euclid(self.settlement_interval as i64)
+                == 0
+    }
+
+    /// Stats bucket for the day containing `time`, reset if it still holds an older day
+    pub fn daily_stats_mut(&mut self, time: i64) -> &mut DailyStats {
+        let day = time.div_euclid(SECONDS_PER_DAY);
+        let stats = &mut self.daily_stats[day.rem_euclid(STATS_HISTORY_DAYS as i64) as usize];
+        if stats.day != day {
+            *stats = DailyStats {
+                day,
+                ..DailyStats::default()
+            };
+        }
+        stats
+    }
+
+    /// Record USDC deposited into escrows (created, funded or matched stakes)
+    pub fn record_volume(&mut self, time: i64, amount: u64) {
+        self.total_volume = self.total_volume.checked_add(amount).unwrap();
+        let stats = self.daily_stats_mut(time);
+        stats.volume = stats.volume.checked_add(amount).unwrap();
+    }
+
+    pub fn record_match(&mut self, time: i64) {
+        self.total_matched_count = self.total_matched_count.checked_add(1).unwrap();
+        let stats = self.daily_stats_mut(time);
+        stats.matched_count = stats.matched_count.checked_add(1).unwrap();
+    }
+
+    pub fn record_settlement(&mut self, time: i64) {
+        self.total_settled_count = self.total_settled_count.checked_add(1).unwrap();
+        let stats = self.daily_stats_mut(time);
+        stats.settled_count = stats.settled_count.checked_add(1).unwrap();
+    }
+
+    pub fn record_fees(&mut self, time: i64, amount: u64) {
+        self.total_fees_collected = self.total_fees_collected.checked_add(amount).unwrap();
+        let stats = self.daily_stats_mut(time);
+        stats.fees = stats.fees.checked_add(amount).unwrap();
+    }
+
     /// Get token name as a string, removing null bytes
     pub fn get_token_name(&self) -> String {
         let end = self.token_name.iter().position(|&b| b == 0).unwrap_or(40);
@@ -68,8 +298,79 @@ impl BettingMarket {
     }
 }
 
+/// Loads markets, rejecting ones still in the Borsh layout. `AccountLoader`
+/// only checks the discriminator, and would slice past the end of their data.
+pub trait MarketLoader {
+    fn load_market(&self) -> Result<Ref<'_, BettingMarket>>;
+    fn load_market_mut(&self) -> Result<RefMut<'_, BettingMarket>>;
+}
+
+impl MarketLoader for AccountLoader<'_, BettingMarket> {
+    fn load_market(&self) -> Result<Ref<'_, BettingMarket>> {
+        require_market_layout(self)?;
+        let market = self.load()?;
+        require!(
+            market.version == BettingMarket::VERSION,
+            ErrorCode::AccountNeedsMigration
+        );
+        Ok(market)
+    }
+
+    fn load_market_mut(&self) -> Result<RefMut<'_, BettingMarket>> {
+        require_market_layout(self)?;
+        let market = self.load_mut()?;
+        require!(
+            market.version == BettingMarket::VERSION,
+            ErrorCode::AccountNeedsMigration
+        );
+        Ok(market)
+    }
+}
+
+fn require_market_layout(loader: &AccountLoader<'_, BettingMarket>) -> Result<()> {
+    require!(
+        loader.as_ref().data_len() >= BettingMarket::LEN,
+        ErrorCode::AccountNeedsMigration
+    );
+    Ok(())
+}
+
+/// Borsh layout of `BettingMarket` before it moved to zero-copy (v0 and v1).
+/// v0 accounts are the same minus the trailing fields; read them zero-extended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LegacyBettingMarket {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub fee_claimer: Pubkey,
+    pub token_name: [u8; 40],
+    pub feed_id: [u8; 32],
+    pub oracle_account: Pubkey,
+    pub bet_count: u64,
+    pub total_volume: u64,
+    pub total_matched_count: u64,
+    pub total_settled_count: u64,
+    pub total_fees_collected: u64,
+    pub match_filter: Pubkey,
+    pub min_stake: u64,
+    pub max_stake: u64,
+    pub max_open_interest: u64,
+    pub open_interest: u64,
+    pub min_duration: u32,
+    pub max_duration: u32,
+    pub settlement_interval: u32,
+    pub settlement_offset: u32,
+    pub market_index: Option<u16>,
+    pub status: MarketStatus,
+    pub open_bet_count: u64,
+    pub version: u8,
+}
+
+impl LegacyBettingMarket {
+    pub const LEN: usize = 8 + LegacyBettingMarket::INIT_SPACE; // discriminator + fields
+}
+
 #[account]
-#[derive(Default)]
+#[derive(InitSpace)]
 pub struct Bet {
     pub bump: u8,
     pub market: Pubkey,
@@ -85,35 +386,1506 @@ pub struct Bet {
     pub winner: Option<Pubkey>,
     pub matcher: Option<Pubkey>,
     pub escrow: Pubkey,
-    pub bet_count: u64, // Store the bet count used in PDA derivation
+    pub bet_count: u64,             // Store the bet count used in PDA derivation
+    pub funder: Pubkey, // Account that deposited the better's stake (default until funded)
+    pub gift_winnings: bool, // If a sponsor funded the bet, pay winnings to the better instead of the sponsor
+    pub memo: [u8; 64],      // Human-readable terms, UTF-8 - fixed 64 bytes
+    pub version: u8,         // Layout version; new fields must be appended after this
+    pub reference_price: u64, // Price recorded at match time for percentage-move bets
+    pub matched_at: i64,     // When the bet was matched (0 for bets matched before v3)
+    pub other_reference_price: u64, // Second market's price at match time
+    pub recurrence_interval: i64, // Seconds between rounds of a recurring bet, 0 for one-off bets
+    pub remaining_rounds: u16, // Rounds still to roll over after this one
+    pub settles_on_round: bool, // Settles on its slot's SettlementRound rather than spot, fixed at creation
+}
+
+impl Default for Bet {
+    fn default() -> Self {
+        Self {
+            bump: 0,
+            market: Pubkey::default(),
+            better: Pubkey::default(),
+            amount: 0,
+            price_threshold: 0,
+            price_direction: PriceDirection::default(),
+            settlement_time: 0,
+            is_matched: false,
+            is_settled: false,
+            is_funded: false,
+            created_by_admin: false,
+            winner: None,
+            matcher: None,
+            escrow: Pubkey::default(),
+            bet_count: 0,
+            funder: Pubkey::default(),
+            gift_winnings: false,
+            memo: [0u8; 64],
+            version: Bet::VERSION,
+            reference_price: 0,
+            matched_at: 0,
+            other_reference_price: 0,
+            recurrence_interval: 0,
+            remaining_rounds: 0,
+            settles_on_round: false,
+        }
+    }
 }
 
 impl Bet {
-    pub const LEN: usize = 8 + // discriminator
-        32 +               // market
-        32 +               // better
-        8 +                // amount
-        8 +                // price_threshold
-        1 +                // price_direction
-        8 +                // settlement_time
-        1 +                // is_matched
-        1 +                // is_settled
-        1 +                // is_funded (new)
-        1 +                // created_by_admin (new)
-        1 + 32 +           // winner (Option<Pubkey>)
-        1 + 32 +           // matcher (Option<Pubkey>)
-        32 +               // escrow
-        8 +                // bet_count
-        1; // bump
+    pub const LEN: usize = 8 + Bet::INIT_SPACE; // discriminator + fields
+
+    /// Current layout version
+    pub const VERSION: u8 = 6;
+
+    /// Upgrade a bet loaded from an older layout. Fields added since then
+    /// have already been zero-filled by the resize.
+    pub fn upgrade(&mut self, market: &BettingMarket) {
+        if self.version == 0 {
+            // v0 bets always refunded and paid the better, so treat them as self-funded
+            if self.is_funded {
+                self.funder = self.better;
+            }
+            // and keep paying winnings to the better if a sponsor funds one later
+            self.gift_winnings = true;
+        }
+        // v1 -> v2: reference_price is only set for percentage-move bets, which are new
+        // v2 -> v3: matched_at is only read by touch bets, which are new
+        // v3 -> v4: other_reference_price is only set for relative-performance bets
+        // v4 -> v5: older bets don't recur
+        if self.version < 6 {
+            // Older bets followed the market's schedule; pin it as it is now
+            self.settles_on_round = market.is_settlement_slot(self.settlement_time);
+        }
+        self.version = Self::VERSION;
+    }
+
+    /// Set the memo from a string, rejecting anything that doesn't fit
+    pub fn set_memo(&mut self, memo: &str) -> Result<()> {
+        self.memo = encode_memo(memo)?;
+        Ok(())
+    }
+
+    /// Get the memo as a string, removing null bytes
+    pub fn get_memo(&self) -> String {
+        let end = self.memo.iter().position(|&b| b == 0).unwrap_or(MEMO_LEN);
+        String::from_utf8_lossy(&self.memo[..end]).to_string()
+    }
+
+    /// Account that gets the better's stake back when the bet is closed or voided
+    pub fn refund_recipient(&self) -> Pubkey {
+        self.funder
+    }
+
+    /// Whoever funded the better's stake, if that is neither party to the bet
+    pub fn sponsor(&self) -> Option<Pubkey> {
+        (self.funder != self.better && Some(self.funder) != self.matcher).then_some(self.funder)
+    }
+
+    /// Whether another round should be rolled over once this one settles
+    pub fn recurs(&self) -> bool {
+        self.recurrence_interval > 0 && self.remaining_rounds > 0
+    }
+
+    /// Earliest time the next round can be rolled over, leaving both parties
+    /// time to see the result and stop the series first
+    pub fn rollover_opens_at(&self) -> i64 {
+        self.settlement_time + ROLLOVER_DELAY
+    }
+
+    /// The next round of a recurring bet: same parties and terms, already
+    /// matched, settling one interval later. The caller fills in the PDA,
+    /// escrow and match time.
+    pub fn next_round(&self) -> Bet {
+        Bet {
+            market: self.market,
+            better: self.better,
+            amount: self.amount,
+            price_threshold: self.price_threshold,
+            price_direction: self.price_direction,
+            settlement_time: self.settlement_time + self.recurrence_interval,
+            is_matched: true,
+            is_funded: true,
+            matcher: self.matcher,
+            funder: self.better,
+            memo: self.memo,
+            recurrence_interval: self.recurrence_interval,
+            remaining_rounds: self.remaining_rounds - 1,
+            ..Bet::default()
+        }
+    }
+
+    /// Whether the better wins at `price`; otherwise the matcher does.
+    /// `other_price` is the second market's price for relative-performance bets.
+    pub fn better_wins(&self, price: u64, other_price: u64) -> bool {
+        match self.price_direction {
+            PriceDirection::Outperform { .. } => {
+                // price / reference > other_price / other_reference, cross-multiplied
+                price as u128 * self.other_reference_price as u128
+                    > other_price as u128 * self.reference_price as u128
+            }
+            direction => direction.better_wins(price, self.price_threshold, self.reference_price),
+        }
+    }
+
+    /// Account entitled to claim the escrow once the bet is settled.
+    /// A sponsor who funded someone else's bet keeps the winnings unless they gifted them.
+    pub fn payout_recipient(&self) -> Option<Pubkey> {
+        self.winner.map(|winner| {
+            if winner == self.better && !self.gift_winnings {
+                self.funder
+            } else {
+                winner
+            }
+        })
+    }
+}
+
+/// Lifetime betting stats for one wallet
+#[account]
+#[derive(Default, InitSpace)]
+pub struct UserProfile {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub bets_created: u64,
+    pub bets_matched: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,       // Bets voided without a winner, returning both stakes
+    pub total_staked: u64, // USDC this user put at risk on bets that settled, including bets they sponsored
+    pub total_won: u64,    // USDC paid out to this user from winning bets, after fees
+    pub fees_paid: u64,
+    pub net_pnl: i64,         // total_won minus total_staked
+    pub bet_index_count: u64, // Number of UserBetIndex entries written for this wallet
+}
+
+impl UserProfile {
+    pub const LEN: usize = 8 + UserProfile::INIT_SPACE; // discriminator + fields
+
+    /// Claim the next slot in this user's bet index
+    pub fn next_bet_index(&mut self) -> u64 {
+        let index = self.bet_index_count;
+        self.bet_index_count = index.checked_add(1).unwrap();
+        index
+    }
+
+    /// Record the outcome of a settled bet this user was a party to and staked
+    pub fn record_settlement(&mut self, won: bool, stake: u64) {
+        self.record_result(won);
+        self.record_stake(stake);
+    }
+
+    /// Record whether this user's side of a settled bet won
+    pub fn record_result(&mut self, won: bool) {
+        if won {
+            self.wins = self.wins.checked_add(1).unwrap();
+        } else {
+            self.losses = self.losses.checked_add(1).unwrap();
+        }
+    }
+
+    /// Record a stake this user had at risk on a settled bet
+    pub fn record_stake(&mut self, stake: u64) {
+        self.total_staked = self.total_staked.checked_add(stake).unwrap();
+        self.net_pnl = self.net_pnl.checked_sub(stake as i64).unwrap();
+    }
+
+    /// Record a bet this user was a party to being voided
+    pub fn record_push(&mut self) {
+        self.pushes = self.pushes.checked_add(1).unwrap();
+    }
+
+    /// Record winnings paid out to this user from a settled bet
+    pub fn record_claim(&mut self, payout: u64, fee: u64) {
+        self.total_won = self.total_won.checked_add(payout).unwrap();
+        self.fees_paid = self.fees_paid.checked_add(fee).unwrap();
+        self.net_pnl = self.net_pnl.checked_add(payout as i64).unwrap();
+    }
+}
+
+/// One entry in a wallet's list of bets, seeded by wallet and a per-wallet counter
+#[account]
+#[derive(Default, InitSpace)]
+pub struct UserBetIndex {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub index: u64,
+    pub bet: Pubkey,
+}
+
+impl UserBetIndex {
+    pub const LEN: usize = 8 + UserBetIndex::INIT_SPACE; // discriminator + fields
+}
+
+/// Directory of every market, so clients can discover markets with one fetch
+#[account]
+#[derive(Default, InitSpace)]
+pub struct MarketRegistry {
+    pub bump: u8,
+    pub authority: Pubkey,
+    #[max_len(MAX_REGISTRY_MARKETS)]
+    pub markets: Vec<MarketEntry>,
+}
+
+impl MarketRegistry {
+    pub const LEN: usize = 8 + MarketRegistry::INIT_SPACE; // discriminator + fields
+
+    /// Add a market to the registry
+    pub fn register(&mut self, market_key: Pubkey, market: &BettingMarket) -> Result<()> {
+        // Entries marked closed before closing removed them only take up room
+        self.markets
+            .retain(|entry| entry.status != MarketStatus::Closed);
+        require!(
+            !self.markets.iter().any(|entry| entry.market == market_key),
+            ErrorCode::MarketAlreadyRegistered
+        );
+        require!(
+            self.markets.len() < MAX_REGISTRY_MARKETS,
+            ErrorCode::MarketRegistryFull
+        );
+        self.markets.push(MarketEntry {
+            market: market_key,
+            token_name: market.token_name,
+            feed_id: market.feed_id,
+            status: MarketStatus::Active,
+        });
+        Ok(())
+    }
+
+    /// Update the status of a registered market
+    pub fn set_status(&mut self, market_key: &Pubkey, status: MarketStatus) -> Result<()> {
+        let entry = self
+            .markets
+            .iter_mut()
+            .find(|entry| entry.market == *market_key)
+            .ok_or(ErrorCode::MarketNotRegistered)?;
+        entry.status = status;
+        Ok(())
+    }
+
+    /// Drop a closed market, freeing its slot for a new one
+    pub fn remove(&mut self, market_key: &Pubkey) -> Result<()> {
+        let index = self
+            .markets
+            .iter()
+            .position(|entry| entry.market == *market_key)
+            .ok_or(ErrorCode::MarketNotRegistered)?;
+        self.markets.remove(index);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct MarketEntry {
+    pub market: Pubkey,
+    pub token_name: [u8; 40],
+    pub feed_id: [u8; 32],
+    pub status: MarketStatus,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
+pub enum MarketStatus {
+    #[default]
+    Active,
+    CloseOnly, // No new bets or matches; existing bets can still settle, claim and close
+    Closed,
+}
+
+/// Official price for one settlement slot, shared by every bet settling at that time
+#[account]
+#[derive(Default, InitSpace)]
+pub struct SettlementRound {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub settlement_time: i64, // Slot this round settles
+    pub price: u64,           // Normalized price recorded for the slot
+    pub publish_time: i64,    // Pyth publish time of the recorded price
+    pub recorded_by: Pubkey,
+    pub sample_count: u32, // Prices averaged into `price` (TWAP markets take several)
+    pub price_sum: u128,   // Sum of the sampled prices
+}
+
+impl SettlementRound {
+    pub const LEN: usize = 8 + SettlementRound::INIT_SPACE; // discriminator + fields
+
+    /// Add a price to the round; `price` becomes the average of all samples
+    pub fn add_sample(&mut self, price: u64, publish_time: i64) {
+        self.sample_count = self.sample_count.checked_add(1).unwrap();
+        self.price_sum = self.price_sum.checked_add(price as u128).unwrap();
+        self.price = (self.price_sum / self.sample_count as u128) as u64;
+        self.publish_time = publish_time;
+    }
+
+    /// Whether bets can settle on the round yet. Averaged rounds wait for their
+    /// window to close and their minimum samples, but one still short of
+    /// samples completes on the ones it has once another window has passed,
+    /// so it can never strand its bets.
+    pub fn is_complete(&self, market: &BettingMarket, current_time: i64) -> bool {
+        if market.twap_window == 0 {
+            return true;
+        }
+        let window_end = self.settlement_time + market.twap_window as i64;
+        current_time >= window_end
+            && (self.sample_count >= market.twap_min_samples
+                || current_time >= window_end + market.twap_window as i64)
+    }
+}
+
+/// Parimutuel pool on one condition for a settlement slot. Yes backs the
+/// condition, No opposes it, and the winning side splits the losing side pro rata.
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Pool {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub settlement_time: i64, // Settlement slot the pool resolves on
+    pub price_threshold: u64,
+    pub price_direction: PriceDirection, // Condition Yes backs
+    pub escrow: Pubkey,
+    pub yes_total: u64,
+    pub no_total: u64,
+    pub position_count: u64, // Positions ever opened, used in position PDA derivation
+    pub is_settled: bool,
+    pub winning_side: Option<PoolSide>, // None once settled means a side was empty and stakes are refunded
+    pub settlement_price: u64,
+    pub positions_closed: u64, // Positions claimed so far; the escrow closes with the last one
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + Pool::INIT_SPACE; // discriminator + fields
+
+    /// Settle on `price`. If nobody took one side there is nothing to win, so
+    /// every position is refunded instead.
+    pub fn settle(&mut self, price: u64) {
+        self.is_settled = true;
+        self.settlement_price = price;
+        self.winning_side = if self.yes_total == 0 || self.no_total == 0 {
+            None
+        } else if self
+            .price_direction
+            .better_wins(price, self.price_threshold, 0)
+        {
+            Some(PoolSide::Yes)
+        } else {
+            Some(PoolSide::No)
+        };
+    }
+
+    /// Settle without a price, refunding every position
+    pub fn void(&mut self) {
+        self.is_settled = true;
+        self.winning_side = None;
+    }
+
+    /// Count a claimed position; true once every position has been claimed
+    pub fn close_position(&mut self) -> bool {
+        self.positions_closed = self.positions_closed.checked_add(1).unwrap();
+        self.positions_closed == self.position_count
+    }
+
+    /// Amount owed to a settled position before fees: its stake plus its share
+    /// of the losing side, nothing if it lost, or its stake back on a refund.
+    /// Shares round down, so a little dust can be left in the escrow.
+    pub fn payout(&self, side: PoolSide, amount: u64) -> u64 {
+        let (winning_total, losing_total) = match side {
+            PoolSide::Yes => (self.yes_total, self.no_total),
+            PoolSide::No => (self.no_total, self.yes_total),
+        };
+        match self.winning_side {
+            None => amount,
+            Some(winning_side) if winning_side == side => {
+                let share = amount as u128 * losing_total as u128 / winning_total as u128;
+                amount.checked_add(share as u64).unwrap()
+            }
+            Some(_) => 0,
+        }
+    }
+}
+
+/// One deposit into a pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct PoolPosition {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub side: PoolSide,
+    pub amount: u64,
+    pub position_index: u64, // Pool's position count when opened, used in PDA derivation
+}
+
+impl PoolPosition {
+    pub const LEN: usize = 8 + PoolPosition::INIT_SPACE; // discriminator + fields
+}
+
+/// Multi-outcome pool on where the price settles. `boundaries` split the
+/// price line into buckets: bucket 0 is below the first boundary, bucket i
+/// covers [boundaries[i - 1], boundaries[i]) and the last bucket is at or
+/// above the final boundary. The winning bucket splits the whole pot pro rata.
+#[account]
+#[derive(Default, InitSpace)]
+pub struct BucketPool {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub settlement_time: i64, // Settlement slot the pool resolves on
+    #[max_len(MAX_BUCKET_BOUNDARIES)]
+    pub boundaries: Vec<u64>, // Strictly increasing
+    #[max_len(MAX_BUCKETS)]
+    pub totals: Vec<u64>, // Deposits per bucket
+    pub escrow: Pubkey,
+    pub position_count: u64, // Positions ever opened, used in position PDA derivation
+    pub is_settled: bool,
+    pub winning_bucket: Option<u8>, // None once settled means nobody won anything and stakes are refunded
+    pub settlement_price: u64,
+    pub positions_closed: u64, // Positions claimed so far; the escrow closes with the last one
+}
+
+impl BucketPool {
+    pub const LEN: usize = 8 + BucketPool::INIT_SPACE; // discriminator + fields
+
+    /// PDA seed identifying a set of boundaries, which don't fit in a seed directly
+    pub fn boundaries_seed(boundaries: &[u64]) -> [u8; 32] {
+        let bytes: Vec<u8> = boundaries.iter().flat_map(|b| b.to_le_bytes()).collect();
+        anchor_lang::solana_program::hash::hash(&bytes).to_bytes()
+    }
+
+    pub fn validate_boundaries(boundaries: &[u64]) -> Result<()> {
+        require!(
+            !boundaries.is_empty()
+                && boundaries.len() <= MAX_BUCKET_BOUNDARIES
+                && boundaries[0] > 0
+                && boundaries.windows(2).all(|pair| pair[0] < pair[1]),
+            ErrorCode::InvalidBuckets
+        );
+        Ok(())
+    }
+
+    /// Bucket the price falls in
+    pub fn bucket_for(&self, price: u64) -> u8 {
+        self.boundaries
+            .partition_point(|&boundary| boundary <= price) as u8
+    }
+
+    pub fn total(&self) -> u64 {
+        self.totals.iter().sum()
+    }
+
+    /// Settle on `price`. If nobody backed the winning bucket, or nobody backed
+    /// any other, there is nothing to win, so every position is refunded.
+    pub fn settle(&mut self, price: u64) {
+        let bucket = self.bucket_for(price);
+        let winning_total = self.totals[bucket as usize];
+        self.is_settled = true;
+        self.settlement_price = price;
+        self.winning_bucket =
+            (winning_total != 0 && winning_total != self.total()).then_some(bucket);
+    }
+
+    /// Settle without a price, refunding every position
+    pub fn void(&mut self) {
+        self.is_settled = true;
+        self.winning_bucket = None;
+    }
+
+    /// Count a claimed position; true once every position has been claimed
+    pub fn close_position(&mut self) -> bool {
+        self.positions_closed = self.positions_closed.checked_add(1).unwrap();
+        self.positions_closed == self.position_count
+    }
+
+    /// Amount owed to a settled position before fees: its share of the whole
+    /// pot, nothing if it lost, or its stake back on a refund. Shares round
+    /// down, so a little dust can be left in the escrow.
+    pub fn payout(&self, bucket: u8, amount: u64) -> u64 {
+        match self.winning_bucket {
+            None => amount,
+            Some(winning_bucket) if winning_bucket == bucket => {
+                let winning_total = self.totals[bucket as usize];
+                (amount as u128 * self.total() as u128 / winning_total as u128) as u64
+            }
+            Some(_) => 0,
+        }
+    }
+}
+
+/// One deposit into a bucket pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct BucketPosition {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub bucket: u8,
+    pub amount: u64,
+    pub position_index: u64, // Pool's position count when opened, used in PDA derivation
+}
+
+impl BucketPosition {
+    pub const LEN: usize = 8 + BucketPosition::INIT_SPACE; // discriminator + fields
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
+pub enum PoolSide {
+    #[default]
+    Yes,
+    No,
+}
+
+/// Standing terms a creator offers to many takers. The creator's capital sits
+/// in one vault, and each take carves out an independent matched `Bet`.
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub offer_id: u64, // Chosen by the creator, used in PDA derivation
+    pub price_threshold: u64,
+    pub price_direction: PriceDirection,
+    pub settlement_time: i64,
+    pub capacity: u64,  // Total stake deposited by the creator
+    pub remaining: u64, // Stake not yet taken
+    pub bets_taken: u64,
+    pub vault: Pubkey,
+    pub memo: [u8; 64], // Copied to every bet taken from the offer
 }
 
+impl Default for Offer {
+    fn default() -> Self {
+        Self {
+            bump: 0,
+            market: Pubkey::default(),
+            creator: Pubkey::default(),
+            offer_id: 0,
+            price_threshold: 0,
+            price_direction: PriceDirection::default(),
+            settlement_time: 0,
+            capacity: 0,
+            remaining: 0,
+            bets_taken: 0,
+            vault: Pubkey::default(),
+            memo: [0u8; MEMO_LEN],
+        }
+    }
+}
+
+impl Offer {
+    pub const LEN: usize = 8 + Offer::INIT_SPACE; // discriminator + fields
+
+    /// Take `amount` of the remaining capacity, returning the matched bet it
+    /// becomes. The caller fills in the PDA, escrow and match time.
+    pub fn take(&mut self, taker: Pubkey, amount: u64) -> Result<Bet> {
+        require!(
+            amount > 0 && amount <= self.remaining,
+            ErrorCode::OfferCapacityExceeded
+        );
+        self.remaining -= amount;
+        self.bets_taken = self.bets_taken.checked_add(1).unwrap();
+
+        Ok(Bet {
+            market: self.market,
+            better: self.creator,
+            amount,
+            price_threshold: self.price_threshold,
+            price_direction: self.price_direction,
+            settlement_time: self.settlement_time,
+            is_matched: true,
+            is_funded: true,
+            matcher: Some(taker),
+            funder: self.creator,
+            memo: self.memo,
+            ..Bet::default()
+        })
+    }
+}
+
+/// One condition of a parlay, priced like a bet on its own market
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
+pub struct ParlayLeg {
+    pub market: Pubkey,
+    pub price_threshold: u64,
+    pub price_direction: PriceDirection,
+    pub settlement_time: i64,
+}
+
+/// An all-or-nothing ticket over several legs, matched by one counterparty.
+/// The better wins only if every leg wins.
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Parlay {
+    pub bump: u8,
+    pub better: Pubkey,
+    pub parlay_id: u64,      // Chosen by the better, used in PDA derivation
+    pub amount: u64,         // Better's stake
+    pub matcher_amount: u64, // Stake the counterparty puts up, setting the odds
+    #[max_len(MAX_PARLAY_LEGS)]
+    pub legs: Vec<ParlayLeg>,
+    #[max_len(MAX_PARLAY_LEGS)]
+    pub leg_prices: Vec<Option<u64>>, // Settlement price of each leg once known
+    #[max_len(MAX_PARLAY_LEGS)]
+    pub legs_on_round: Vec<bool>, // Whether each leg settles on a SettlementRound, fixed at creation
+    pub is_matched: bool,
+    pub matcher: Option<Pubkey>,
+    pub is_settled: bool,
+    pub winner: Option<Pubkey>,
+    pub escrow: Pubkey,
+}
+
+impl Parlay {
+    pub const LEN: usize = 8 + Parlay::INIT_SPACE; // discriminator + fields
+
+    pub fn validate_legs(legs: &[ParlayLeg]) -> Result<()> {
+        require!(
+            (2..=MAX_PARLAY_LEGS).contains(&legs.len())
+                && legs.iter().all(|leg| leg.price_direction.is_absolute()),
+            ErrorCode::InvalidParlayLegs
+        );
+        for leg in legs {
+            leg.price_direction
+                .validate(leg.market, leg.price_threshold)?;
+        }
+        Ok(())
+    }
+
+    /// The parlay can't be matched once its first leg is due
+    pub fn first_settlement_time(&self) -> i64 {
+        self.legs
+            .iter()
+            .map(|leg| leg.settlement_time)
+            .min()
+            .unwrap_or_default()
+    }
+
+    pub fn all_legs_priced(&self) -> bool {
+        self.leg_prices.iter().all(Option::is_some)
+    }
+
+    /// Split a fee evenly across the legs' markets; the first leg takes any
+    /// remainder
+    pub fn fee_shares(&self, fee_amount: u64) -> Vec<u64> {
+        let legs = self.legs.len() as u64;
+        let share = fee_amount / legs;
+        (0..legs)
+            .map(|leg| {
+                if leg == 0 {
+                    fee_amount - share * (legs - 1)
+                } else {
+                    share
+                }
+            })
+            .collect()
+    }
+
+    /// Whether every leg has been priced and won
+    pub fn better_wins(&self) -> bool {
+        self.legs.iter().zip(&self.leg_prices).all(|(leg, price)| {
+            price.is_some_and(|price| {
+                leg.price_direction
+                    .better_wins(price, leg.price_threshold, 0)
+            })
+        })
+    }
+}
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct MatchFilter {
+    pub bump: u8,
+    pub market: Pubkey,
+    pub mode: FilterMode,
+    #[max_len(MAX_FILTER_WALLETS)]
+    pub wallets: Vec<Pubkey>,
+}
+
+impl MatchFilter {
+    pub const LEN: usize = 8 + MatchFilter::INIT_SPACE; // discriminator + fields
+
+    /// Whether `matcher` may match bets on this market
+    pub fn allows(&self, matcher: &Pubkey) -> bool {
+        match self.mode {
+            FilterMode::Disabled => true,
+            FilterMode::Allowlist => self.wallets.contains(matcher),
+            FilterMode::Denylist => !self.wallets.contains(matcher),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
+pub enum FilterMode {
+    #[default]
+    Disabled,
+    Allowlist, // Only listed wallets can match
+    Denylist,  // Listed wallets (e.g. known linked accounts) cannot match
+}
+
+/// Which Pyth price a market settles on
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PriceSource {
+    #[default]
+    Spot,
+    Ema, // Pyth's exponentially-weighted moving average, less sensitive to brief wicks
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace)]
 pub enum PriceDirection {
     #[default]
     Above,
     Below,
+    /// Better wins if low <= price <= high
+    Between {
+        low: u64,
+        high: u64,
+    },
+    /// Better wins if price < low or price > high
+    Outside {
+        low: u64,
+        high: u64,
+    },
+    /// Better wins if price >= reference * (1 + bps / 10_000)
+    UpBy {
+        bps: u32,
+    },
+    /// Better wins if price <= reference * (1 - bps / 10_000)
+    DownBy {
+        bps: u32,
+    },
+    /// Better wins if price reaches the barrier at any point before settlement
+    TouchAbove {
+        barrier: u64,
+    },
+    /// Better wins if price falls to the barrier at any point before settlement
+    TouchBelow {
+        barrier: u64,
+    },
+    /// Better wins if this market's asset gains more, in percentage terms,
+    /// than `other_market`'s between match time and settlement
+    Outperform {
+        other_market: Pubkey,
+    },
+}
+
+impl PriceDirection {
+    /// Whether the bet is measured against the price at match time
+    pub fn is_relative(&self) -> bool {
+        matches!(
+            self,
+            PriceDirection::UpBy { .. }
+                | PriceDirection::DownBy { .. }
+                | PriceDirection::Outperform { .. }
+        )
+    }
+
+    /// Second market whose price a relative-performance bet also needs
+    pub fn other_market(&self) -> Option<Pubkey> {
+        match *self {
+            PriceDirection::Outperform { other_market } => Some(other_market),
+            _ => None,
+        }
+    }
+
+    /// Whether the bet can be settled early by `trigger_barrier`
+    pub fn is_touch(&self) -> bool {
+        matches!(
+            self,
+            PriceDirection::TouchAbove { .. } | PriceDirection::TouchBelow { .. }
+        )
+    }
+
+    /// Check the bet's price terms for a bet on `market`. Only `Above` and
+    /// `Below` use `price_threshold`.
+    pub fn validate(&self, market: Pubkey, price_threshold: u64) -> Result<()> {
+        match *self {
+            PriceDirection::Above | PriceDirection::Below => {
+                require!(price_threshold > 0, ErrorCode::InvalidPriceThreshold)
+            }
+            PriceDirection::Between { low, high } | PriceDirection::Outside { low, high } => {
+                require!(low < high, ErrorCode::InvalidPriceRange)
+            }
+            PriceDirection::TouchAbove { barrier } | PriceDirection::TouchBelow { barrier } => {
+                require!(barrier > 0, ErrorCode::InvalidPriceThreshold)
+            }
+            PriceDirection::UpBy { bps } => require!(bps > 0, ErrorCode::InvalidPriceMove),
+            PriceDirection::DownBy { bps } => require!(
+                bps > 0 && (bps as u64) < BPS_DENOMINATOR,
+                ErrorCode::InvalidPriceMove
+            ),
+            PriceDirection::Outperform { other_market } => {
+                require_keys_neq!(other_market, market, ErrorCode::InvalidOtherMarket)
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the better wins at `price`; otherwise the matcher does.
+    /// `reference_price` is only used by percentage-move bets. Relative-performance
+    /// bets need both markets' prices, so use `Bet::better_wins` for those.
+    pub fn better_wins(&self, price: u64, price_threshold: u64, reference_price: u64) -> bool {
+        // Compare price * 10_000 against reference * (10_000 ± bps) to avoid rounding
+        let scaled_price = price as u128 * BPS_DENOMINATOR as u128;
+        match *self {
+            PriceDirection::Above => price > price_threshold,
+            PriceDirection::Below => price < price_threshold,
+            PriceDirection::Between { low, high } => (low..=high).contains(&price),
+            PriceDirection::Outside { low, high } => !(low..=high).contains(&price),
+            PriceDirection::UpBy { bps } => {
+                scaled_price >= reference_price as u128 * (BPS_DENOMINATOR as u128 + bps as u128)
+            }
+            PriceDirection::DownBy { bps } => {
+                scaled_price <= reference_price as u128 * (BPS_DENOMINATOR as u128 - bps as u128)
+            }
+            PriceDirection::TouchAbove { barrier } => price >= barrier,
+            PriceDirection::TouchBelow { barrier } => price <= barrier,
+            PriceDirection::Outperform { .. } => false,
+        }
+    }
+
+    /// Whether the outcome depends only on the settlement price, so the bet
+    /// can be pooled or used as a parlay leg
+    pub fn is_absolute(&self) -> bool {
+        matches!(
+            self,
+            PriceDirection::Above
+                | PriceDirection::Below
+                | PriceDirection::Between { .. }
+                | PriceDirection::Outside { .. }
+        )
+    }
+
+    /// Borsh encoding, used as a PDA seed
+    pub fn seed(&self) -> Vec<u8> {
+        self.try_to_vec().unwrap()
+    }
+
+    /// Human-readable terms for logs, e.g. "above 100" or "between 90 and 110"
+    pub fn describe(&self, price_threshold: u64) -> String {
+        match *self {
+            PriceDirection::Above => format!("above {}", price_threshold),
+            PriceDirection::Below => format!("below {}", price_threshold),
+            PriceDirection::Between { low, high } => format!("between {} and {}", low, high),
+            PriceDirection::Outside { low, high } => format!("outside {} and {}", low, high),
+            PriceDirection::UpBy { bps } => format!("up {} bps from the match price", bps),
+            PriceDirection::DownBy { bps } => format!("down {} bps from the match price", bps),
+            PriceDirection::TouchAbove { barrier } => format!("touching {} from below", barrier),
+            PriceDirection::TouchBelow { barrier } => format!("touching {} from above", barrier),
+            PriceDirection::Outperform { other_market } => {
+                format!("outperforming {}", other_market)
+            }
+        }
+    }
 }
 
 // Fee constants
 pub const FEE_PERCENTAGE: u64 = 3; // 3% fee
 pub const FEE_DENOMINATOR: u64 = 100;
+
+// Denominator for percentage moves quoted in basis points
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Default minimum stake when a market doesn't set one
+pub const MIN_STAKE: u64 = 1_000_000; // 1 USDC
+
+// Default settlement horizon when a market doesn't set one
+pub const DEFAULT_MIN_DURATION: i64 = 3600; // 1 hour
+pub const DEFAULT_MAX_DURATION: i64 = 90 * 24 * 3600; // 90 days
+
+// How long a matched bet must have failed to settle before the admin can void it
+pub const VOID_DELAY: i64 = 7 * 24 * 3600; // 7 days
+
+// How long after a round's settlement time either party can still stop a recurring bet
+pub const ROLLOVER_DELAY: i64 = 3600; // 1 hour
+
+// Days of per-market stats kept in the ring buffer
+pub const STATS_HISTORY_DAYS: usize = 30;
+pub const SECONDS_PER_DAY: i64 = 24 * 3600;
+
+// Maximum size of a bet memo in bytes
+pub const MEMO_LEN: usize = 64;
+
+// Maximum number of markets in the market registry
+pub const MAX_REGISTRY_MARKETS: usize = 64;
+
+// Maximum number of wallets in a market's match filter
+pub const MAX_FILTER_WALLETS: usize = 32;
+
+// Maximum number of outcomes in a bucket pool
+pub const MAX_BUCKETS: usize = 16;
+pub const MAX_BUCKET_BOUNDARIES: usize = MAX_BUCKETS - 1;
+
+// Maximum number of legs in a parlay
+pub const MAX_PARLAY_LEGS: usize = 8;
+
+/// Encode a memo string into its fixed-size field, rejecting anything that doesn't fit
+pub fn encode_memo(memo: &str) -> Result<[u8; MEMO_LEN]> {
+    let bytes = memo.as_bytes();
+    require!(bytes.len() <= MEMO_LEN, ErrorCode::MemoTooLong);
+    // Null bytes are used as padding, so they can't appear in the memo itself
+    require!(!bytes.contains(&0), ErrorCode::InvalidMemo);
+    let mut encoded = [0u8; MEMO_LEN];
+    encoded[..bytes.len()].copy_from_slice(bytes);
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.len()
+    }
+
+    fn legacy_market(market_index: Option<u16>) -> LegacyBettingMarket {
+        let mut legacy =
+            LegacyBettingMarket::deserialize(&mut &[0u8; LegacyBettingMarket::LEN][..]).unwrap();
+        legacy.market_index = market_index;
+        legacy
+    }
+
+    #[test]
+    fn betting_market_fits_len() {
+        // Migration grows accounts in place, which is capped at 10 KiB per instruction
+        const { assert!(BettingMarket::LEN <= 10 * 1024) };
+        const { assert!(BettingMarket::LEN > LegacyBettingMarket::LEN) };
+
+        let mut data = Vec::new();
+        legacy_market(Some(u16::MAX)).serialize(&mut data).unwrap();
+        assert_eq!(8 + data.len(), LegacyBettingMarket::LEN);
+    }
+
+    #[test]
+    fn migrate_from_keeps_seeds_and_status() {
+        let mut legacy = legacy_market(None);
+        legacy.feed_id = [7u8; 32];
+        legacy.bet_count = 42;
+        legacy.status = MarketStatus::CloseOnly;
+
+        let mut market: BettingMarket = bytemuck::Zeroable::zeroed();
+        market.migrate_from(&legacy);
+        assert_eq!(market.bet_count, 42);
+        assert!(market.status() == MarketStatus::CloseOnly);
+        assert_eq!(market.market_index(), None);
+        assert_eq!(market.feed_id_seed(), &[7u8; 8]);
+        assert!(market.market_index_seed().is_empty());
+        assert_eq!(market.version, BettingMarket::VERSION);
+
+        market.migrate_from(&legacy_market(Some(3)));
+        assert_eq!(market.market_index(), Some(3));
+        assert_eq!(market.market_index_seed(), 3u16.to_le_bytes());
+    }
+
+    #[test]
+    fn migrated_open_bet_count_is_untracked_until_backfilled() {
+        let mut market: BettingMarket = bytemuck::Zeroable::zeroed();
+        market.migrate_from(&legacy_market(Some(0)));
+        assert_eq!(market.open_bets_untracked, 1);
+
+        // Bets opened before migration may release a count that never had them
+        market.remove_open_bet().unwrap();
+        assert_eq!(market.open_bet_count, 0);
+
+        market.open_bets_untracked = 0;
+        market.add_open_bet();
+        market.remove_open_bet().unwrap();
+        assert!(market.remove_open_bet().is_err());
+    }
+
+    #[test]
+    fn daily_stats_roll_over_stale_days() {
+        let mut market: BettingMarket = bytemuck::Zeroable::zeroed();
+        let day = 20_000 * SECONDS_PER_DAY;
+
+        market.record_volume(day, 5);
+        market.record_volume(day + SECONDS_PER_DAY - 1, 7);
+        market.record_match(day);
+        assert_eq!(market.daily_stats_mut(day).volume, 12);
+        assert_eq!(market.daily_stats_mut(day).matched_count, 1);
+
+        // The same slot a full history later starts from zero
+        let later = day + STATS_HISTORY_DAYS as i64 * SECONDS_PER_DAY;
+        market.record_fees(later, 3);
+        let stats = market.daily_stats_mut(later);
+        assert_eq!((stats.volume, stats.fees), (0, 3));
+        assert_eq!(market.total_volume, 12);
+        assert_eq!(market.total_fees_collected, 3);
+    }
+
+    #[test]
+    fn bet_fits_len() {
+        let bet = Bet {
+            winner: Some(Pubkey::new_unique()),
+            matcher: Some(Pubkey::new_unique()),
+            price_direction: PriceDirection::Outperform {
+                other_market: Pubkey::new_unique(),
+            },
+            memo: [b'x'; MEMO_LEN],
+            ..Bet::default()
+        };
+        assert_eq!(serialized_len(&bet), Bet::LEN);
+    }
+
+    #[test]
+    fn only_third_party_funders_are_sponsors() {
+        let better = Pubkey::new_unique();
+        let matcher = Pubkey::new_unique();
+        let mut bet = Bet {
+            better,
+            funder: better,
+            matcher: Some(matcher),
+            ..Bet::default()
+        };
+        assert_eq!(bet.sponsor(), None);
+        bet.funder = matcher;
+        assert_eq!(bet.sponsor(), None);
+        let sponsor = Pubkey::new_unique();
+        bet.funder = sponsor;
+        assert_eq!(bet.sponsor(), Some(sponsor));
+
+        // A sponsor's stake counts against their own PnL, not the better's
+        let mut profile = UserProfile::default();
+        profile.record_stake(10);
+        profile.record_claim(15, 1);
+        assert_eq!(profile.net_pnl, 5);
+        assert_eq!((profile.wins, profile.losses), (0, 0));
+    }
+
+    #[test]
+    fn upgraded_v0_bets_keep_paying_the_better() {
+        let better = Pubkey::new_unique();
+        let mut bet = Bet {
+            better,
+            is_funded: false,
+            version: 0,
+            ..Bet::default()
+        };
+        bet.upgrade(&bytemuck::Zeroable::zeroed());
+        assert_eq!(bet.version, Bet::VERSION);
+
+        // A sponsor funding the migrated bet doesn't take the winnings
+        bet.funder = Pubkey::new_unique();
+        bet.winner = Some(better);
+        assert_eq!(bet.payout_recipient(), Some(better));
+    }
+
+    #[test]
+    fn next_round_keeps_terms_and_counts_down() {
+        let better = Pubkey::new_unique();
+        let bet = Bet {
+            better,
+            funder: better,
+            matcher: Some(Pubkey::new_unique()),
+            amount: 10_000_000,
+            price_threshold: 3_000,
+            settlement_time: 1_700_000_000,
+            is_matched: true,
+            is_settled: true,
+            winner: Some(better),
+            recurrence_interval: 7 * SECONDS_PER_DAY,
+            remaining_rounds: 2,
+            ..Bet::default()
+        };
+        assert!(bet.recurs());
+        assert_eq!(bet.rollover_opens_at(), 1_700_000_000 + ROLLOVER_DELAY);
+
+        let next = bet.next_round();
+        assert_eq!(next.settlement_time, 1_700_000_000 + 7 * SECONDS_PER_DAY);
+        assert_eq!(next.remaining_rounds, 1);
+        assert_eq!(next.matcher, bet.matcher);
+        assert!(next.is_matched && !next.is_settled);
+        assert_eq!(next.winner, None);
+
+        // The last round doesn't roll over again
+        assert!(!next.next_round().recurs());
+    }
+
+    #[test]
+    fn offer_spawns_matched_bets_until_capacity_runs_out() {
+        let creator = Pubkey::new_unique();
+        let taker = Pubkey::new_unique();
+        let mut offer = Offer {
+            creator,
+            price_threshold: 3_000,
+            capacity: 30_000_000,
+            remaining: 30_000_000,
+            memo: encode_memo("loser buys dinner").unwrap(),
+            ..Offer::default()
+        };
+
+        let bet = offer.take(taker, 20_000_000).unwrap();
+        assert_eq!(bet.better, creator);
+        assert_eq!(bet.funder, creator);
+        assert_eq!(bet.matcher, Some(taker));
+        assert!(bet.is_matched && bet.is_funded);
+        assert_eq!(bet.get_memo(), "loser buys dinner");
+        assert_eq!(offer.remaining, 10_000_000);
+
+        assert!(offer.take(taker, 10_000_001).is_err());
+        assert!(offer.take(taker, 10_000_000).is_ok());
+        assert_eq!((offer.remaining, offer.bets_taken), (0, 2));
+    }
+
+    #[test]
+    fn fixed_size_accounts_fit_len() {
+        assert_eq!(serialized_len(&UserProfile::default()), UserProfile::LEN);
+        assert_eq!(serialized_len(&UserBetIndex::default()), UserBetIndex::LEN);
+        assert_eq!(
+            serialized_len(&SettlementRound::default()),
+            SettlementRound::LEN
+        );
+        assert_eq!(serialized_len(&PoolPosition::default()), PoolPosition::LEN);
+        assert_eq!(
+            serialized_len(&BucketPosition::default()),
+            BucketPosition::LEN
+        );
+        let offer = Offer {
+            price_direction: PriceDirection::Outperform {
+                other_market: Pubkey::new_unique(),
+            },
+            ..Offer::default()
+        };
+        assert_eq!(serialized_len(&offer), Offer::LEN);
+    }
+
+    #[test]
+    fn full_market_registry_fits_len() {
+        let entry = MarketEntry {
+            market: Pubkey::new_unique(),
+            token_name: [b'x'; 40],
+            feed_id: [1u8; 32],
+            status: MarketStatus::Closed,
+        };
+        let registry = MarketRegistry {
+            markets: vec![entry; MAX_REGISTRY_MARKETS],
+            ..MarketRegistry::default()
+        };
+        assert_eq!(serialized_len(&registry), MarketRegistry::LEN);
+    }
+
+    #[test]
+    fn closed_markets_free_registry_slots() {
+        let market: BettingMarket = bytemuck::Zeroable::zeroed();
+        let mut registry = MarketRegistry::default();
+        let keys: Vec<Pubkey> = (0..MAX_REGISTRY_MARKETS)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        for key in &keys {
+            registry.register(*key, &market).unwrap();
+        }
+        assert!(registry.register(Pubkey::new_unique(), &market).is_err());
+
+        registry.remove(&keys[0]).unwrap();
+        assert!(registry.remove(&keys[0]).is_err());
+        registry.register(Pubkey::new_unique(), &market).unwrap();
+
+        // Entries left marked closed are dropped when room is needed
+        registry.set_status(&keys[1], MarketStatus::Closed).unwrap();
+        registry.register(Pubkey::new_unique(), &market).unwrap();
+        assert_eq!(registry.markets.len(), MAX_REGISTRY_MARKETS);
+    }
+
+    #[test]
+    fn full_match_filter_fits_len() {
+        let match_filter = MatchFilter {
+            mode: FilterMode::Denylist,
+            wallets: vec![Pubkey::new_unique(); MAX_FILTER_WALLETS],
+            ..MatchFilter::default()
+        };
+        assert_eq!(serialized_len(&match_filter), MatchFilter::LEN);
+    }
+
+    #[test]
+    fn range_bets_resolve_inclusive_bands() {
+        let between = PriceDirection::Between { low: 90, high: 110 };
+        let outside = PriceDirection::Outside { low: 90, high: 110 };
+        for (price, inside) in [
+            (89, false),
+            (90, true),
+            (100, true),
+            (110, true),
+            (111, false),
+        ] {
+            assert_eq!(between.better_wins(price, 0, 0), inside);
+            assert_eq!(outside.better_wins(price, 0, 0), !inside);
+        }
+
+        assert!(between.validate(Pubkey::default(), 0).is_ok());
+        assert!(PriceDirection::Between {
+            low: 100,
+            high: 100
+        }
+        .validate(Pubkey::default(), 0)
+        .is_err());
+        assert!(PriceDirection::Above
+            .validate(Pubkey::default(), 0)
+            .is_err());
+    }
+
+    #[test]
+    fn percentage_moves_compare_against_reference() {
+        let up = PriceDirection::UpBy { bps: 500 };
+        assert!(!up.better_wins(104, 0, 100));
+        assert!(up.better_wins(105, 0, 100));
+        // 5% of 101 is 5.05, so 106 isn't enough
+        assert!(!up.better_wins(106, 0, 101));
+
+        let down = PriceDirection::DownBy { bps: 500 };
+        assert!(down.better_wins(95, 0, 100));
+        assert!(!down.better_wins(96, 0, 100));
+
+        assert!(up.validate(Pubkey::default(), 0).is_ok());
+        assert!(PriceDirection::UpBy { bps: 0 }
+            .validate(Pubkey::default(), 0)
+            .is_err());
+        assert!(PriceDirection::DownBy { bps: 10_000 }
+            .validate(Pubkey::default(), 0)
+            .is_err());
+    }
+
+    #[test]
+    fn touch_bets_win_at_the_barrier() {
+        let above = PriceDirection::TouchAbove { barrier: 5_000 };
+        assert!(above.is_touch());
+        assert!(above.better_wins(5_000, 0, 0));
+        assert!(!above.better_wins(4_999, 0, 0));
+
+        let below = PriceDirection::TouchBelow { barrier: 5_000 };
+        assert!(below.better_wins(5_000, 0, 0));
+        assert!(!below.better_wins(5_001, 0, 0));
+        assert!(PriceDirection::TouchBelow { barrier: 0 }
+            .validate(Pubkey::default(), 0)
+            .is_err());
+    }
+
+    #[test]
+    fn outperform_compares_percentage_changes() {
+        let market = Pubkey::new_unique();
+        let other_market = Pubkey::new_unique();
+        let direction = PriceDirection::Outperform { other_market };
+        let bet = Bet {
+            price_direction: direction,
+            reference_price: 100,
+            other_reference_price: 2_000,
+            ..Bet::default()
+        };
+
+        // +10% vs +5%
+        assert!(bet.better_wins(110, 2_100));
+        // +10% vs +10% is a tie, which goes to the matcher
+        assert!(!bet.better_wins(110, 2_200));
+        // -5% vs -10%
+        assert!(bet.better_wins(95, 1_800));
+
+        assert!(direction.validate(market, 0).is_ok());
+        assert!(direction.validate(other_market, 0).is_err());
+    }
+
+    #[test]
+    fn settlement_round_averages_samples() {
+        let mut round = SettlementRound::default();
+        round.add_sample(100, 10);
+        assert_eq!(round.price, 100);
+        round.add_sample(103, 20);
+        round.add_sample(103, 30);
+        assert_eq!((round.price, round.sample_count), (102, 3));
+        assert_eq!(round.publish_time, 30);
+    }
+
+    #[test]
+    fn short_twap_rounds_complete_after_a_grace_window() {
+        let mut market: BettingMarket = bytemuck::Zeroable::zeroed();
+        market.twap_window = 300;
+        market.twap_min_samples = 5;
+        let mut round = SettlementRound {
+            settlement_time: 1_000,
+            ..SettlementRound::default()
+        };
+        round.add_sample(100, 1_000);
+
+        assert!(!round.is_complete(&market, 1_299));
+        assert!(!round.is_complete(&market, 1_300));
+        assert!(round.is_complete(&market, 1_600));
+
+        for publish_time in [1_060, 1_120, 1_180, 1_240] {
+            round.add_sample(100, publish_time);
+        }
+        assert!(round.is_complete(&market, 1_300));
+    }
+
+    #[test]
+    fn pool_fits_len() {
+        let pool = Pool {
+            price_direction: PriceDirection::Outperform {
+                other_market: Pubkey::new_unique(),
+            },
+            winning_side: Some(PoolSide::No),
+            ..Pool::default()
+        };
+        assert_eq!(serialized_len(&pool), Pool::LEN);
+    }
+
+    #[test]
+    fn pool_splits_losing_side_pro_rata() {
+        let mut pool = Pool {
+            price_threshold: 100,
+            price_direction: PriceDirection::Above,
+            yes_total: 300,
+            no_total: 100,
+            ..Pool::default()
+        };
+        pool.settle(101);
+        assert!(pool.winning_side == Some(PoolSide::Yes));
+        assert_eq!(pool.payout(PoolSide::Yes, 200), 266);
+        assert_eq!(pool.payout(PoolSide::Yes, 100), 133);
+        assert_eq!(pool.payout(PoolSide::No, 100), 0);
+
+        pool.settle(100);
+        assert!(pool.winning_side == Some(PoolSide::No));
+        assert_eq!(pool.payout(PoolSide::No, 100), 400);
+    }
+
+    #[test]
+    fn one_sided_pool_refunds_everyone() {
+        let mut pool = Pool {
+            price_threshold: 100,
+            yes_total: 500,
+            ..Pool::default()
+        };
+        pool.settle(200);
+        assert!(pool.is_settled && pool.winning_side.is_none());
+        assert_eq!(pool.payout(PoolSide::Yes, 500), 500);
+    }
+
+    #[test]
+    fn voided_pool_refunds_and_closes_with_last_position() {
+        let mut pool = Pool {
+            price_threshold: 100,
+            yes_total: 300,
+            no_total: 100,
+            position_count: 2,
+            ..Pool::default()
+        };
+        pool.void();
+        assert!(pool.is_settled && pool.winning_side.is_none());
+        assert_eq!(pool.payout(PoolSide::No, 100), 100);
+
+        assert!(!pool.close_position());
+        assert!(pool.close_position());
+    }
+
+    #[test]
+    fn full_bucket_pool_fits_len() {
+        let pool = BucketPool {
+            boundaries: vec![u64::MAX; MAX_BUCKET_BOUNDARIES],
+            totals: vec![u64::MAX; MAX_BUCKETS],
+            winning_bucket: Some(0),
+            ..BucketPool::default()
+        };
+        assert_eq!(serialized_len(&pool), BucketPool::LEN);
+    }
+
+    #[test]
+    fn bucket_pool_pays_winning_bucket_from_whole_pot() {
+        let boundaries = vec![90, 100, 110];
+        assert!(BucketPool::validate_boundaries(&boundaries).is_ok());
+        assert!(BucketPool::validate_boundaries(&[100, 100]).is_err());
+        assert!(BucketPool::validate_boundaries(&[]).is_err());
+
+        let mut pool = BucketPool {
+            boundaries,
+            totals: vec![100, 200, 0, 100],
+            ..BucketPool::default()
+        };
+        assert_eq!(pool.bucket_for(89), 0);
+        assert_eq!(pool.bucket_for(90), 1);
+        assert_eq!(pool.bucket_for(109), 2);
+        assert_eq!(pool.bucket_for(110), 3);
+
+        pool.settle(95);
+        assert_eq!(pool.winning_bucket, Some(1));
+        assert_eq!(pool.payout(1, 50), 100);
+        assert_eq!(pool.payout(0, 100), 0);
+
+        // Nobody backed 100-110, so everyone gets their stake back
+        pool.settle(105);
+        assert_eq!(pool.winning_bucket, None);
+        assert_eq!(pool.payout(0, 100), 100);
+    }
+
+    fn parlay_leg(price_direction: PriceDirection, price_threshold: u64) -> ParlayLeg {
+        ParlayLeg {
+            market: Pubkey::new_unique(),
+            price_threshold,
+            price_direction,
+            settlement_time: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn full_parlay_fits_len() {
+        // Not a valid leg, but the largest direction
+        let other_market = Pubkey::new_unique();
+        let leg = parlay_leg(PriceDirection::Outperform { other_market }, u64::MAX);
+        let parlay = Parlay {
+            legs: vec![leg; MAX_PARLAY_LEGS],
+            leg_prices: vec![Some(u64::MAX); MAX_PARLAY_LEGS],
+            legs_on_round: vec![true; MAX_PARLAY_LEGS],
+            matcher: Some(Pubkey::new_unique()),
+            winner: Some(Pubkey::new_unique()),
+            ..Parlay::default()
+        };
+        assert_eq!(serialized_len(&parlay), Parlay::LEN);
+    }
+
+    #[test]
+    fn parlay_wins_only_if_every_leg_wins() {
+        let legs = vec![
+            parlay_leg(PriceDirection::Above, 100),
+            parlay_leg(PriceDirection::Between { low: 10, high: 20 }, 0),
+        ];
+        assert!(Parlay::validate_legs(&legs).is_ok());
+        assert!(Parlay::validate_legs(&legs[..1]).is_err());
+        assert!(Parlay::validate_legs(&[
+            legs[0],
+            parlay_leg(PriceDirection::UpBy { bps: 500 }, 0)
+        ])
+        .is_err());
+
+        let mut parlay = Parlay {
+            leg_prices: vec![Some(150), None],
+            legs,
+            ..Parlay::default()
+        };
+        assert!(!parlay.all_legs_priced());
+        assert!(!parlay.better_wins());
+
+        parlay.leg_prices[1] = Some(15);
+        assert!(parlay.all_legs_priced());
+        assert!(parlay.better_wins());
+
+        parlay.leg_prices[1] = Some(25);
+        assert!(!parlay.better_wins());
+    }
+
+    #[test]
+    fn parlay_fee_splits_across_legs() {
+        let parlay = Parlay {
+            legs: vec![parlay_leg(PriceDirection::Above, 100); 3],
+            ..Parlay::default()
+        };
+        assert_eq!(parlay.fee_shares(300), vec![100, 100, 100]);
+        assert_eq!(parlay.fee_shares(302), vec![102, 100, 100]);
+        assert_eq!(parlay.fee_shares(2), vec![2, 0, 0]);
+    }
+
+    #[test]
+    fn memo_round_trips_and_rejects_oversized_input() {
+        let mut bet = Bet::default();
+        bet.set_memo("loser buys dinner").unwrap();
+        assert_eq!(bet.get_memo(), "loser buys dinner");
+
+        bet.set_memo(&"x".repeat(MEMO_LEN)).unwrap();
+        assert_eq!(bet.get_memo().len(), MEMO_LEN);
+
+        assert!(bet.set_memo(&"x".repeat(MEMO_LEN + 1)).is_err());
+        assert!(bet.set_memo("nul\0byte").is_err());
+    }
+}
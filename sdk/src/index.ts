@@ -13,6 +13,7 @@ import {
   PublicKey,
   SystemProgram,
   Transaction,
+  TransactionInstruction,
   TransactionMessage,
   VersionedTransaction,
 } from "@solana/web3.js";
@@ -80,8 +81,26 @@ export class FriendbetSDK {
   /**
    * Find a market account PDA
    */
-  async findMarketAddress(feedIdHex: string): Promise<[PublicKey, number]> {
-    // Convert hex string to bytes and take first 8 bytes for PDA derivation
+  async findMarketAddress(
+    feedIdHex: string,
+    marketIndex: number = 0
+  ): Promise<[PublicKey, number]> {
+    // Markets are derived from the full 32-byte feed id plus a u16 market index
+    const feedIdBytes = Buffer.from(feedIdHex.replace("0x", ""), "hex");
+    const marketIndexBytes = Buffer.alloc(2);
+    marketIndexBytes.writeUInt16LE(marketIndex);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), feedIdBytes, marketIndexBytes],
+      this.programId
+    );
+  }
+
+  /**
+   * Find a market PDA created before full-width seeds (first 8 feed id bytes only)
+   */
+  async findLegacyMarketAddress(
+    feedIdHex: string
+  ): Promise<[PublicKey, number]> {
     const feedIdBytes = Buffer.from(feedIdHex.replace("0x", ""), "hex");
     return PublicKey.findProgramAddressSync(
       [Buffer.from("market"), feedIdBytes.subarray(0, 8)],
@@ -89,6 +108,27 @@ export class FriendbetSDK {
     );
   }
 
+  /**
+   * Find the market registry PDA
+   */
+  findRegistryAddress(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("registry")],
+      this.programId
+    );
+  }
+
+  /**
+   * Fetch every active market from the market registry
+   */
+  async getActiveMarkets() {
+    const [registry] = this.findRegistryAddress();
+    const { markets } = await this.program.account.marketRegistry.fetch(
+      registry
+    );
+    return markets.filter((entry) => "active" in entry.status);
+  }
+
   /**
    * Find a bet account PDA
    */
@@ -106,6 +146,79 @@ export class FriendbetSDK {
     );
   }
 
+  /**
+   * Find a user profile PDA
+   */
+  findProfileAddress(owner: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("profile"), owner.toBuffer()],
+      this.programId
+    );
+  }
+
+  /**
+   * Create a user profile, paid for by this wallet. Anyone can set one up
+   * for another wallet, e.g. a counterparty who needs one to settle a bet.
+   */
+  async initializeProfile(
+    owner: PublicKey = this.userWallet.publicKey
+  ): Promise<string> {
+    return this.program.methods
+      .initializeProfile(owner)
+      .accounts({ payer: this.userWallet.publicKey })
+      .rpc();
+  }
+
+  /**
+   * Instructions creating a user profile if it doesn't exist yet, to prepend
+   * to anything that requires one
+   */
+  async initializeProfileIfNeeded(
+    owner: PublicKey
+  ): Promise<TransactionInstruction[]> {
+    const [profile] = this.findProfileAddress(owner);
+    if (await this.connection.getAccountInfo(profile)) {
+      return [];
+    }
+    return [
+      await this.program.methods
+        .initializeProfile(owner)
+        .accounts({ payer: this.userWallet.publicKey })
+        .instruction(),
+    ];
+  }
+
+  /**
+   * Find a user bet index entry PDA
+   */
+  findUserBetIndexAddress(owner: PublicKey, index: BN): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("bet_index"),
+        owner.toBuffer(),
+        index.toArrayLike(Buffer, "le", 8),
+      ],
+      this.programId
+    );
+  }
+
+  /**
+   * List the bets a wallet created or matched, oldest first
+   */
+  async getUserBets(owner: PublicKey): Promise<PublicKey[]> {
+    const [profile] = this.findProfileAddress(owner);
+    const { betIndexCount } = await this.program.account.userProfile.fetch(
+      profile
+    );
+    const indexAddresses = [...Array(betIndexCount.toNumber()).keys()].map(
+      (i) => this.findUserBetIndexAddress(owner, new BN(i))[0]
+    );
+    const entries = await this.program.account.userBetIndex.fetchMultiple(
+      indexAddresses
+    );
+    return entries.filter((entry) => entry !== null).map((entry) => entry.bet);
+  }
+
   /**
    * Initialize a new betting market
    */
@@ -113,13 +226,14 @@ export class FriendbetSDK {
     tokenName: string,
     feeClaimer: PublicKey,
     feedIdHex: string,
-    priceUpdate: PublicKey
+    priceUpdate: PublicKey,
+    marketIndex: number = 0
   ): Promise<string> {
     const authority = this.provider.wallet.publicKey;
-    const [marketPda, _] = await this.findMarketAddress(feedIdHex);
+    const [marketPda, _] = await this.findMarketAddress(feedIdHex, marketIndex);
 
     const tx = await this.program.methods
-      .initializeMarket(tokenName, feeClaimer, feedIdHex)
+      .initializeMarket(tokenName, feeClaimer, feedIdHex, marketIndex)
       .accounts({
         authority,
         market: marketPda,
@@ -143,7 +257,12 @@ export class FriendbetSDK {
   }
 
   // create bet
-  async createBet(marketId: PublicKey, amount: number, feedIdHex: string) {
+  async createBet(
+    marketId: PublicKey,
+    amount: number,
+    feedIdHex: string,
+    memo: string = ""
+  ) {
     const betCount = (await this.findBetCountForMarket(marketId)).toNumber();
 
     const [bet] = await this.findBetAddress(marketId, new BN(betCount + 1));
@@ -155,7 +274,7 @@ export class FriendbetSDK {
     const betEscrow = getAssociatedTokenAddressSync(USDC_MINT, bet);
 
     const createBetIx = await this.program.methods
-      .createBet(new BN(amount), new BN(0), { above: {} }, new BN(0))
+      .createBet(new BN(amount), new BN(0), { above: {} }, new BN(0), memo)
       .accounts({
         market: marketId,
         betterTokenAccount,
@@ -175,6 +294,7 @@ export class FriendbetSDK {
         ComputeBudgetProgram.setComputeUnitPrice({
           microLamports: 200_000,
         }),
+        ...(await this.initializeProfileIfNeeded(this.userWallet.publicKey)),
         createBetIx,
       ],
     }).compileToV0Message();
@@ -185,7 +305,17 @@ export class FriendbetSDK {
     return signature;
   }
 
-  async matchBet(marketId: PublicKey, amount: number) {
+  /**
+   * The recurrence schedule the matcher agrees to. The better can change it
+   * until the bet is matched, so pass the terms you were shown; the defaults
+   * only match one-off bets.
+   */
+  async matchBet(
+    marketId: PublicKey,
+    amount: number,
+    expectedRecurrenceInterval: number = 0,
+    expectedRemainingRounds: number = 0
+  ) {
     const betCount = (await this.findBetCountForMarket(marketId)).toNumber();
 
     const [bet] = await this.findBetAddress(marketId, new BN(betCount));
@@ -198,7 +328,7 @@ export class FriendbetSDK {
     );
 
     const matchBetIx = await this.program.methods
-      .matchBet()
+      .matchBet(new BN(expectedRecurrenceInterval), expectedRemainingRounds)
       .accounts({
         market: marketId,
         betEscrow,
@@ -216,6 +346,7 @@ export class FriendbetSDK {
         ComputeBudgetProgram.setComputeUnitPrice({
           microLamports: 200_000,
         }),
+        ...(await this.initializeProfileIfNeeded(this.userWallet.publicKey)),
         matchBetIx,
       ],
     }).compileToV0Message();
@@ -272,9 +403,11 @@ export class FriendbetSDK {
 
     const [bet] = await this.findBetAddress(marketId, new BN(betCount));
     const betEscrow = getAssociatedTokenAddressSync(USDC_MINT, bet, true);
-    const betterTokenAccount = getAssociatedTokenAddressSync(
+    // Refunds go to whoever funded the bet, which may be a sponsor
+    const { funder, isFunded } = await this.program.account.bet.fetch(bet);
+    const refundTokenAccount = getAssociatedTokenAddressSync(
       USDC_MINT,
-      better
+      isFunded ? funder : better
     );
 
     const closeBetIx = await this.program.methods
@@ -285,7 +418,7 @@ export class FriendbetSDK {
         closer: this.userWallet.publicKey,
         market: marketId,
         betEscrow,
-        betterTokenAccount,
+        refundTokenAccount,
       })
       .instruction();
 
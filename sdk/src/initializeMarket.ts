@@ -37,11 +37,7 @@ async function main() {
 
   // Let's also manually check what bytes we're using for derivation
   const feedIdBytes = Buffer.from(feedIdHex.replace("0x", ""), "hex");
-  console.log("Feed ID bytes (first 8):", feedIdBytes.subarray(0, 8));
-  console.log(
-    "Feed ID bytes (first 8) as hex:",
-    feedIdBytes.subarray(0, 8).toString("hex")
-  );
+  console.log("Feed ID bytes as hex:", feedIdBytes.toString("hex"));
 
   // Check what program ID we're using - need to access it through the SDK
   // Let's add a method to get the program ID
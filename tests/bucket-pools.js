@@ -0,0 +1,250 @@
+const { SystemProgram } = require("@solana/web3.js");
+const { TOKEN_PROGRAM_ID } = require("@solana/spl-token");
+const { assert } = require("chai");
+const {
+  BN,
+  USDC,
+  USDC_MINT,
+  HOUR,
+  VOID_DELAY,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  profilePda,
+  roundPda,
+  bucketPoolPda,
+  bucketPositionPda,
+  setTokenAccount,
+  tokenBalance,
+  wallet,
+  createMarket,
+  updateSettlementBounds,
+  recordSettlementRound,
+} = require("./helpers/bankrun");
+
+// Below 95k, from 95k up to 105k, and 105k or more
+const BOUNDARIES = [95_000, 105_000];
+
+describe("bucket pools", () => {
+  let env, market, alice, bob, carol;
+
+  const createBucketPoolIx = (settlementTime, boundaries) => {
+    const pool = bucketPoolPda(env, market.address, settlementTime, boundaries);
+    const poolEscrow = setTokenAccount(env, pool, 0);
+    const builder = env.program.methods
+      .createBucketPool(
+        new BN(settlementTime),
+        boundaries.map((boundary) => new BN(boundary))
+      )
+      .accountsStrict({
+        payer: env.payer.publicKey,
+        market: market.address,
+        pool,
+        usdcMint: USDC_MINT,
+        poolEscrow,
+        systemProgram: SystemProgram.programId,
+      });
+    return { builder, pool: { address: pool, escrow: poolEscrow } };
+  };
+
+  const createBucketPool = async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    const { builder, pool } = createBucketPoolIx(settlementTime, BOUNDARIES);
+    await send(env, builder);
+    return { ...pool, settlementTime };
+  };
+
+  const enterBucketPoolIx = async (pool, owner, bucket, amount) => {
+    const { positionCount } = await env.program.account.bucketPool.fetch(
+      pool.address
+    );
+    const position = bucketPositionPda(
+      env,
+      pool.address,
+      positionCount.addn(1)
+    );
+    const builder = env.program.methods
+      .enterBucketPool(bucket, new BN(amount * USDC))
+      .accountsStrict({
+        owner: owner.publicKey,
+        market: market.address,
+        pool: pool.address,
+        poolEscrow: pool.escrow,
+        ownerTokenAccount: owner.tokenAccount,
+        position,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    return { builder, position };
+  };
+
+  const enterBucketPool = async (pool, owner, bucket, amount) => {
+    const { builder, position } = await enterBucketPoolIx(
+      pool,
+      owner,
+      bucket,
+      amount
+    );
+    await send(env, builder, [owner.keypair]);
+    return position;
+  };
+
+  const voidBucketPoolIx = (pool) =>
+    env.program.methods.voidBucketPool().accountsStrict({
+      authority: env.payer.publicKey,
+      market: market.address,
+      pool: pool.address,
+    });
+
+  // Losing positions can be closed without either token account
+  const claimBucketPoolIx = (
+    pool,
+    position,
+    owner,
+    { paid = false, feeRecipient = null } = {}
+  ) =>
+    env.program.methods.claimBucketPool().accountsStrict({
+      owner: owner.publicKey,
+      position,
+      pool: pool.address,
+      market: market.address,
+      poolEscrow: pool.escrow,
+      ownerTokenAccount: paid ? owner.tokenAccount : null,
+      feeRecipientTokenAccount: feeRecipient,
+      ownerProfile: profilePda(env, owner.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    alice = await wallet(env);
+    bob = await wallet(env);
+    carol = await wallet(env);
+    await updateSettlementBounds(env, market, { interval: HOUR });
+  });
+
+  it("rejects boundaries that don't rise from above zero", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    for (const boundaries of [[], [105_000, 95_000], [0, 95_000]]) {
+      await expectError(
+        env,
+        createBucketPoolIx(settlementTime, boundaries).builder,
+        [],
+        "InvalidBuckets"
+      );
+    }
+  });
+
+  it("rejects entering a bucket that doesn't exist", async () => {
+    const pool = await createBucketPool();
+    // One bucket more than there are boundaries, counting from zero
+    const { builder } = await enterBucketPoolIx(
+      pool,
+      alice,
+      BOUNDARIES.length + 1,
+      10
+    );
+    await expectError(
+      env,
+      builder,
+      [alice.keypair],
+      "InvalidBucket"
+    );
+  });
+
+  it("splits the whole pot within the winning bucket and sweeps the dust", async () => {
+    const pool = await createBucketPool();
+    const alicePosition = await enterBucketPool(pool, alice, 1, 10);
+    const bobPosition = await enterBucketPool(pool, bob, 1, 20);
+    const carolPosition = await enterBucketPool(pool, carol, 2, 10);
+    const feeRecipient = setTokenAccount(env, market.feeClaimer.publicKey, 0);
+
+    await warpTo(env, pool.settlementTime);
+    await expectError(
+      env,
+      claimBucketPoolIx(pool, carolPosition, carol),
+      [],
+      "PoolNotSettled"
+    );
+    await recordSettlementRound(env, market, pool.settlementTime, 100_000);
+    await send(
+      env,
+      env.program.methods.settleBucketPool().accountsStrict({
+        market: market.address,
+        pool: pool.address,
+        settlementRound: roundPda(env, market.address, pool.settlementTime),
+      })
+    );
+    assert.equal(
+      (await env.program.account.bucketPool.fetch(pool.address))
+        .winningBucket,
+      1
+    );
+
+    await send(env, claimBucketPoolIx(pool, carolPosition, carol));
+    await expectError(
+      env,
+      claimBucketPoolIx(pool, alicePosition, alice),
+      [],
+      "TokenAccountRequired"
+    );
+    // 10/30 and 20/30 of 40 USDC, less the 3% fee
+    const aliceBalance = await tokenBalance(env, alice.tokenAccount);
+    const bobBalance = await tokenBalance(env, bob.tokenAccount);
+    for (const [position, owner] of [
+      [alicePosition, alice],
+      [bobPosition, bob],
+    ]) {
+      await send(
+        env,
+        claimBucketPoolIx(pool, position, owner, { paid: true, feeRecipient })
+      );
+    }
+    assert.equal(
+      (await tokenBalance(env, alice.tokenAccount)) - aliceBalance,
+      12_933_334
+    );
+    assert.equal(
+      (await tokenBalance(env, bob.tokenAccount)) - bobBalance,
+      25_866_667
+    );
+
+    // The last claim sweeps the unallocated micro-USDC and closes the escrow
+    assert.equal(await tokenBalance(env, feeRecipient), 399_999 + 799_999 + 1);
+    assert.isNull(await tokenBalance(env, pool.escrow));
+  });
+
+  it("refunds every position once a pool without a round is voided", async () => {
+    const pool = await createBucketPool();
+    const alicePosition = await enterBucketPool(pool, alice, 0, 10);
+    const bobPosition = await enterBucketPool(pool, bob, 2, 15);
+
+    await warpTo(env, pool.settlementTime + VOID_DELAY - 1);
+    await expectError(env, voidBucketPoolIx(pool), [], "PoolVoidTooEarly");
+    await warpTo(env, pool.settlementTime + VOID_DELAY);
+    await send(env, voidBucketPoolIx(pool));
+    await expectError(env, voidBucketPoolIx(pool), [], "PoolAlreadySettled");
+
+    // Refunds are free, so no fee account is needed
+    const aliceBalance = await tokenBalance(env, alice.tokenAccount);
+    const bobBalance = await tokenBalance(env, bob.tokenAccount);
+    for (const [position, owner] of [
+      [alicePosition, alice],
+      [bobPosition, bob],
+    ]) {
+      await send(env, claimBucketPoolIx(pool, position, owner, { paid: true }));
+    }
+    assert.equal(
+      (await tokenBalance(env, alice.tokenAccount)) - aliceBalance,
+      10 * USDC
+    );
+    assert.equal(
+      (await tokenBalance(env, bob.tokenAccount)) - bobBalance,
+      15 * USDC
+    );
+    assert.isNull(await tokenBalance(env, pool.escrow));
+  });
+});
@@ -0,0 +1,681 @@
+const path = require("path");
+const crypto = require("crypto");
+const anchor = require("@coral-xyz/anchor");
+const {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  ComputeBudgetProgram,
+  LAMPORTS_PER_SOL,
+  SYSVAR_RENT_PUBKEY,
+} = require("@solana/web3.js");
+const {
+  TOKEN_PROGRAM_ID,
+  ACCOUNT_SIZE,
+  AccountLayout,
+  MINT_SIZE,
+  MintLayout,
+} = require("@solana/spl-token");
+const { BN } = require("bn.js");
+const { assert } = require("chai");
+const { start, Clock } = require("solana-bankrun");
+const { BankrunProvider } = require("anchor-bankrun");
+
+// Bankrun loads friendbet.so from here. Build it with
+// `anchor build -- --features test` so admin instructions accept the test payer.
+process.env.SBF_OUT_DIR =
+  process.env.SBF_OUT_DIR || path.join(__dirname, "../../target/deploy");
+
+const IDL = require("../../target/idl/friendbet.json");
+
+const USDC_MINT = new PublicKey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const PYTH_RECEIVER_ID = new PublicKey(
+  "rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ"
+);
+const BTC_FEED =
+  "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+const ETH_FEED =
+  "ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace";
+
+const USDC = 1_000_000;
+const HOUR = 3600;
+const DAY = 24 * HOUR;
+// On an hour boundary, so hourly settlement slots line up with it
+const START_TIME = 1_800_000_000;
+// Pyth prices are posted with 8 decimals and normalized to whole units
+const PRICE_DECIMALS = 8n;
+const VOID_DELAY = 7 * DAY;
+const ROLLOVER_DELAY = HOUR;
+const STALENESS_THRESHOLD = 60;
+
+const u16 = (value) => {
+  const buffer = Buffer.alloc(2);
+  buffer.writeUInt16LE(Number(value));
+  return buffer;
+};
+
+const u64 = (value) => {
+  const buffer = Buffer.alloc(8);
+  buffer.writeBigUInt64LE(BigInt(value.toString()));
+  return buffer;
+};
+
+const i64 = (value) => {
+  const buffer = Buffer.alloc(8);
+  buffer.writeBigInt64LE(BigInt(value.toString()));
+  return buffer;
+};
+
+const usdc = (amount) => new BN(amount * USDC);
+
+// Start a fresh bank with the USDC mint and market registry in place
+async function setup() {
+  const context = await start(
+    [{ name: "friendbet", programId: new PublicKey(IDL.address) }],
+    []
+  );
+  const provider = new BankrunProvider(context);
+  const program = new anchor.Program(IDL, provider);
+  const env = { context, provider, program, payer: context.payer, nonce: 0 };
+
+  const mint = Buffer.alloc(MINT_SIZE);
+  MintLayout.encode(
+    {
+      mintAuthorityOption: 0,
+      mintAuthority: PublicKey.default,
+      supply: BigInt(1_000_000_000) * BigInt(USDC),
+      decimals: 6,
+      isInitialized: true,
+      freezeAuthorityOption: 0,
+      freezeAuthority: PublicKey.default,
+    },
+    mint
+  );
+  context.setAccount(USDC_MINT, {
+    lamports: LAMPORTS_PER_SOL,
+    data: mint,
+    owner: TOKEN_PROGRAM_ID,
+    executable: false,
+  });
+
+  await warpTo(env, START_TIME);
+
+  await send(
+    env,
+    program.methods.initializeRegistry().accountsStrict({
+      authority: env.payer.publicKey,
+      registry: pda(env, "registry"),
+      systemProgram: SystemProgram.programId,
+    })
+  );
+
+  return env;
+}
+
+// Send a transaction built by `builder`, throwing with its logs if it fails
+async function send(env, builder, signers = []) {
+  const { result, meta } = await processTransaction(env, builder, signers);
+  if (result) {
+    throw new Error(`${result}\n${(meta ? meta.logMessages : []).join("\n")}`);
+  }
+  return meta;
+}
+
+// Send a transaction built by `builder` and assert it fails with the
+// program error `code`
+async function expectError(env, builder, signers, code) {
+  const { result, meta } = await processTransaction(env, builder, signers);
+  const logs = (meta ? meta.logMessages : []).join("\n");
+  assert.isNotNull(result, `expected ${code}, but the transaction succeeded`);
+  assert.include(logs, `Error Code: ${code}.`, logs);
+}
+
+async function processTransaction(env, builder, signers) {
+  const tx = await builder
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      // Retrying the same instruction would otherwise be rejected as already processed
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: ++env.nonce }),
+    ])
+    .transaction();
+  tx.recentBlockhash = env.context.lastBlockhash;
+  tx.feePayer = env.payer.publicKey;
+  tx.sign(env.payer, ...signers);
+  return env.context.banksClient.tryProcessTransaction(tx);
+}
+
+async function now(env) {
+  const clock = await env.context.banksClient.getClock();
+  return Number(clock.unixTimestamp);
+}
+
+async function warpTo(env, unixTimestamp) {
+  const clock = await env.context.banksClient.getClock();
+  env.context.setClock(
+    new Clock(
+      clock.slot + 1n,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+}
+
+function pda(env, ...seeds) {
+  return PublicKey.findProgramAddressSync(
+    seeds.map((seed) =>
+      seed instanceof PublicKey
+        ? seed.toBuffer()
+        : typeof seed === "string"
+        ? Buffer.from(seed)
+        : seed
+    ),
+    env.program.programId
+  )[0];
+}
+
+const marketPda = (env, feed, index) =>
+  pda(env, "market", Buffer.from(feed, "hex"), u16(index));
+const betPda = (env, market, betCount) =>
+  pda(env, "bet", market, u64(betCount));
+const profilePda = (env, owner) => pda(env, "profile", owner);
+const betIndexPda = (env, owner, index) =>
+  pda(env, "bet_index", owner, u64(index));
+const roundPda = (env, market, settlementTime) =>
+  pda(env, "round", market, i64(settlementTime));
+const matchFilterPda = (env, market) => pda(env, "match_filter", market);
+const poolPositionPda = (env, pool, index) =>
+  pda(env, "pool_position", pool, u64(index));
+const bucketPositionPda = (env, pool, index) =>
+  pda(env, "bucket_position", pool, u64(index));
+const parlayPda = (env, better, parlayId) =>
+  pda(env, "parlay", better, u64(parlayId));
+const offerPda = (env, market, creator, offerId) =>
+  pda(env, "offer", market, creator, u64(offerId));
+const rolloverAuthorityPda = (env, wallet) => pda(env, "rollover", wallet);
+
+const poolPda = (env, market, settlementTime, priceThreshold, direction) =>
+  pda(
+    env,
+    "pool",
+    market,
+    i64(settlementTime),
+    u64(priceThreshold),
+    // Borsh encoding of the direction, as `PriceDirection::seed` uses
+    env.program.coder.types.encode("PriceDirection", direction)
+  );
+
+const bucketPoolPda = (env, market, settlementTime, boundaries) =>
+  pda(
+    env,
+    "bucket_pool",
+    market,
+    i64(settlementTime),
+    crypto
+      .createHash("sha256")
+      .update(Buffer.concat(boundaries.map(u64)))
+      .digest()
+  );
+
+// Write a USDC token account for `owner` and return its address
+function setTokenAccount(
+  env,
+  owner,
+  amount,
+  { delegate = null, delegatedAmount = 0 } = {}
+) {
+  const address = Keypair.generate().publicKey;
+  const data = Buffer.alloc(ACCOUNT_SIZE);
+  AccountLayout.encode(
+    {
+      mint: USDC_MINT,
+      owner,
+      amount: BigInt(amount.toString()),
+      delegateOption: delegate ? 1 : 0,
+      delegate: delegate || PublicKey.default,
+      state: 1,
+      isNativeOption: 0,
+      isNative: 0n,
+      delegatedAmount: BigInt(delegatedAmount.toString()),
+      closeAuthorityOption: 0,
+      closeAuthority: PublicKey.default,
+    },
+    data
+  );
+  env.context.setAccount(address, {
+    lamports: 2_039_280,
+    data,
+    owner: TOKEN_PROGRAM_ID,
+    executable: false,
+  });
+  return address;
+}
+
+// USDC balance of a token account, or null once it has been closed
+async function tokenBalance(env, address) {
+  const account = await env.context.banksClient.getAccount(address);
+  return account
+    ? Number(AccountLayout.decode(Buffer.from(account.data)).amount)
+    : null;
+}
+
+async function accountExists(env, address) {
+  return (await env.context.banksClient.getAccount(address)) !== null;
+}
+
+async function setRawAccount(env, address, data) {
+  const account = await env.context.banksClient.getAccount(address);
+  env.context.setAccount(address, {
+    lamports: Number(account.lamports),
+    data,
+    owner: account.owner,
+    executable: false,
+  });
+}
+
+// Write a Pyth `PriceUpdateV2` for `feed` at `address`. `price` is in whole
+// units, as the program normalizes it.
+function setPriceUpdate(
+  env,
+  address,
+  feed,
+  price,
+  publishTime,
+  { emaPrice = price, verified = true } = {}
+) {
+  const scale = 10n ** PRICE_DECIMALS;
+  const discriminator = crypto
+    .createHash("sha256")
+    .update("account:PriceUpdateV2")
+    .digest()
+    .subarray(0, 8);
+  const message = Buffer.alloc(84);
+  Buffer.from(feed, "hex").copy(message, 0);
+  message.writeBigInt64LE(BigInt(price) * scale, 32);
+  message.writeBigUInt64LE(0n, 40);
+  message.writeInt32LE(-Number(PRICE_DECIMALS), 48);
+  message.writeBigInt64LE(BigInt(publishTime), 52);
+  message.writeBigInt64LE(BigInt(publishTime - 1), 60);
+  message.writeBigInt64LE(BigInt(emaPrice) * scale, 68);
+  message.writeBigUInt64LE(0n, 76);
+  const data = Buffer.concat([
+    discriminator,
+    Buffer.alloc(32), // write authority
+    verified ? Buffer.from([1]) : Buffer.from([0, 1]), // Full or Partial { 1 }
+    message,
+    u64(0), // posted slot
+    Buffer.alloc(verified ? 1 : 0), // pad Full updates to PriceUpdateV2::LEN
+  ]);
+  env.context.setAccount(address, {
+    lamports: LAMPORTS_PER_SOL,
+    data,
+    owner: PYTH_RECEIVER_ID,
+    executable: false,
+  });
+  return address;
+}
+
+// A separate verified update for `market`'s feed, as posted for a past time
+function priceUpdate(env, market, price, publishTime, options) {
+  return setPriceUpdate(
+    env,
+    Keypair.generate().publicKey,
+    market.feed,
+    price,
+    publishTime,
+    options
+  );
+}
+
+// Move `market`'s oracle account to `price`, published at `publishTime`
+async function setMarketPrice(env, market, price, publishTime) {
+  setPriceUpdate(
+    env,
+    market.priceUpdate,
+    market.feed,
+    price,
+    publishTime === undefined ? await now(env) : publishTime
+  );
+}
+
+// A funded wallet with a USDC account and profile
+async function wallet(env, usdcAmount = 1_000) {
+  const keypair = Keypair.generate();
+  env.context.setAccount(keypair.publicKey, {
+    lamports: 100 * LAMPORTS_PER_SOL,
+    data: Buffer.alloc(0),
+    owner: SystemProgram.programId,
+    executable: false,
+  });
+  const tokenAccount = setTokenAccount(
+    env,
+    keypair.publicKey,
+    usdcAmount * USDC
+  );
+  await send(
+    env,
+    env.program.methods.initializeProfile(keypair.publicKey).accountsStrict({
+      payer: env.payer.publicKey,
+      profile: profilePda(env, keypair.publicKey),
+      systemProgram: SystemProgram.programId,
+    })
+  );
+  return { keypair, publicKey: keypair.publicKey, tokenAccount };
+}
+
+const fetchMarket = (env, market) =>
+  env.program.account.bettingMarket.fetch(market.address);
+const fetchProfile = (env, owner) =>
+  env.program.account.userProfile.fetch(profilePda(env, owner.publicKey));
+
+// Initialize a market on `feed` with its oracle account priced at `price`
+async function createMarket(
+  env,
+  { feed = BTC_FEED, index = 0, tokenName = "BTC", price = 100_000 } = {}
+) {
+  const market = {
+    address: marketPda(env, feed, index),
+    feed,
+    priceUpdate: Keypair.generate().publicKey,
+    feeClaimer: await wallet(env, 0),
+  };
+  setPriceUpdate(env, market.priceUpdate, feed, price, await now(env));
+  await send(
+    env,
+    env.program.methods
+      .initializeMarket(tokenName, market.feeClaimer.publicKey, feed, index)
+      .accountsStrict({
+        authority: env.payer.publicKey,
+        market: market.address,
+        registry: pda(env, "registry"),
+        priceUpdate: market.priceUpdate,
+        systemProgram: SystemProgram.programId,
+      })
+  );
+  return market;
+}
+
+const updateMarketLimits = (
+  env,
+  market,
+  { minStake = USDC, maxStake = 0, maxOpenInterest = 0 }
+) =>
+  send(
+    env,
+    env.program.methods
+      .updateMarketLimits(
+        new BN(minStake),
+        new BN(maxStake),
+        new BN(maxOpenInterest)
+      )
+      .accountsStrict({
+        authority: env.payer.publicKey,
+        market: market.address,
+      })
+  );
+
+const updateSettlementBounds = (
+  env,
+  market,
+  { minDuration = HOUR, maxDuration = 90 * DAY, interval = 0, offset = 0 }
+) =>
+  send(
+    env,
+    env.program.methods
+      .updateSettlementBounds(minDuration, maxDuration, interval, offset)
+      .accountsStrict({
+        authority: env.payer.publicKey,
+        market: market.address,
+      })
+  );
+
+const updatePriceSourceIx = (env, market, priceSource, window, minSamples) =>
+  env.program.methods
+    .updatePriceSource(priceSource, window, minSamples)
+    .accountsStrict({
+      authority: env.payer.publicKey,
+      market: market.address,
+    });
+
+const initializeMatchFilter = (env, market, mode, wallets) =>
+  send(
+    env,
+    env.program.methods
+      .initializeMatchFilter(
+        mode,
+        wallets.map((wallet) => wallet.publicKey)
+      )
+      .accountsStrict({
+        authority: env.payer.publicKey,
+        market: market.address,
+        matchFilter: matchFilterPda(env, market.address),
+        systemProgram: SystemProgram.programId,
+      })
+  );
+
+// Builder for create_bet, with the bet and escrow it will use
+async function createBetIx(
+  env,
+  market,
+  better,
+  {
+    amount = 10 * USDC,
+    priceThreshold = 100_000,
+    direction = { above: {} },
+    settlementTime,
+    memo = "",
+  }
+) {
+  const { betCount } = await fetchMarket(env, market);
+  const bet = betPda(env, market.address, betCount.addn(1));
+  const betEscrow = setTokenAccount(env, bet, 0);
+  const { betIndexCount } = await fetchProfile(env, better);
+  const builder = env.program.methods
+    .createBet(
+      new BN(amount),
+      new BN(priceThreshold),
+      direction,
+      new BN(settlementTime),
+      memo
+    )
+    .accountsStrict({
+      better: better.publicKey,
+      market: market.address,
+      bet,
+      usdcMint: USDC_MINT,
+      betEscrow,
+      betterTokenAccount: better.tokenAccount,
+      betterProfile: profilePda(env, better.publicKey),
+      betterBetIndex: betIndexPda(env, better.publicKey, betIndexCount),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    });
+  return { builder, bet, betEscrow };
+}
+
+async function createBet(env, market, better, options) {
+  const { builder, bet, betEscrow } = await createBetIx(
+    env,
+    market,
+    better,
+    options
+  );
+  await send(env, builder, [better.keypair]);
+  return { address: bet, escrow: betEscrow, market };
+}
+
+// Builder for match_bet. Relative bets pass `withPrice`, outperform bets
+// `otherMarket`, and markets with a filter `withFilter`.
+async function matchBetIx(
+  env,
+  bet,
+  matcher,
+  {
+    expectedRecurrenceInterval = 0,
+    expectedRemainingRounds = 0,
+    withPrice = false,
+    withFilter = false,
+    otherMarket = null,
+  } = {}
+) {
+  const { betIndexCount } = await fetchProfile(env, matcher);
+  return env.program.methods
+    .matchBet(
+      new BN(expectedRecurrenceInterval),
+      expectedRemainingRounds
+    )
+    .accountsStrict({
+      matcher: matcher.publicKey,
+      bet: bet.address,
+      market: bet.market.address,
+      betEscrow: bet.escrow,
+      matcherTokenAccount: matcher.tokenAccount,
+      matchFilter: withFilter ? matchFilterPda(env, bet.market.address) : null,
+      priceUpdate: withPrice || otherMarket ? bet.market.priceUpdate : null,
+      otherMarket: otherMarket ? otherMarket.address : null,
+      otherPriceUpdate: otherMarket ? otherMarket.priceUpdate : null,
+      matcherProfile: profilePda(env, matcher.publicKey),
+      matcherBetIndex: betIndexPda(env, matcher.publicKey, betIndexCount),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+}
+
+async function matchBet(env, bet, matcher, options) {
+  await send(env, await matchBetIx(env, bet, matcher, options), [
+    matcher.keypair,
+  ]);
+}
+
+// Builder for settle_bet, against the oracle account or the bet's round
+async function settleBetIx(
+  env,
+  bet,
+  { onRound = false, otherMarket = null } = {}
+) {
+  const account = await env.program.account.bet.fetch(bet.address);
+  return env.program.methods.settleBet().accountsStrict({
+    authority: env.payer.publicKey,
+    bet: bet.address,
+    market: bet.market.address,
+    priceUpdate: onRound ? null : bet.market.priceUpdate,
+    otherMarket: otherMarket ? otherMarket.address : null,
+    otherPriceUpdate: otherMarket && !onRound ? otherMarket.priceUpdate : null,
+    otherSettlementRound:
+      otherMarket && onRound
+        ? roundPda(env, otherMarket.address, account.settlementTime)
+        : null,
+    settlementRound: onRound
+      ? roundPda(env, bet.market.address, account.settlementTime)
+      : null,
+    betterProfile: profilePda(env, account.better),
+    matcherProfile: profilePda(env, account.matcher),
+    sponsorProfile: null,
+  });
+}
+
+async function settleBet(env, bet, options) {
+  await send(env, await settleBetIx(env, bet, options));
+}
+
+// Builder for claim_funds, paying `claimer`
+function claimFundsIx(env, bet, claimer) {
+  return env.program.methods.claimFunds().accountsStrict({
+    claimer: claimer.publicKey,
+    bet: bet.address,
+    market: bet.market.address,
+    betEscrow: bet.escrow,
+    claimerTokenAccount: claimer.tokenAccount,
+    feeRecipientTokenAccount: setTokenAccount(
+      env,
+      bet.market.feeClaimer.publicKey,
+      0
+    ),
+    claimerProfile: profilePda(env, claimer.publicKey),
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+}
+
+// Builder for record_settlement_round at `settlementTime` from `update`
+function recordSettlementRoundIx(env, market, settlementTime, update) {
+  return env.program.methods
+    .recordSettlementRound(new BN(settlementTime))
+    .accountsStrict({
+      payer: env.payer.publicKey,
+      market: market.address,
+      settlementRound: roundPda(env, market.address, settlementTime),
+      priceUpdate: update,
+      systemProgram: SystemProgram.programId,
+    });
+}
+
+// Record `market`'s round for `settlementTime` at `price`, published at the slot
+function recordSettlementRound(env, market, settlementTime, price) {
+  return send(
+    env,
+    recordSettlementRoundIx(
+      env,
+      market,
+      settlementTime,
+      priceUpdate(env, market, price, settlementTime)
+    )
+  );
+}
+
+module.exports = {
+  BN,
+  USDC,
+  USDC_MINT,
+  HOUR,
+  DAY,
+  START_TIME,
+  VOID_DELAY,
+  ROLLOVER_DELAY,
+  STALENESS_THRESHOLD,
+  BTC_FEED,
+  ETH_FEED,
+  usdc,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  betPda,
+  profilePda,
+  betIndexPda,
+  roundPda,
+  matchFilterPda,
+  poolPda,
+  poolPositionPda,
+  bucketPoolPda,
+  bucketPositionPda,
+  parlayPda,
+  offerPda,
+  rolloverAuthorityPda,
+  setTokenAccount,
+  tokenBalance,
+  accountExists,
+  setRawAccount,
+  priceUpdate,
+  setMarketPrice,
+  wallet,
+  fetchMarket,
+  fetchProfile,
+  createMarket,
+  updateMarketLimits,
+  updateSettlementBounds,
+  updatePriceSourceIx,
+  initializeMatchFilter,
+  createBetIx,
+  createBet,
+  matchBetIx,
+  matchBet,
+  settleBetIx,
+  settleBet,
+  claimFundsIx,
+  recordSettlementRoundIx,
+  recordSettlementRound,
+};
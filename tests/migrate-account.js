@@ -0,0 +1,135 @@
+const { SystemProgram } = require("@solana/web3.js");
+const { assert } = require("chai");
+const {
+  HOUR,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  setRawAccount,
+  setMarketPrice,
+  wallet,
+  createMarket,
+  createBet,
+  matchBetIx,
+  matchBet,
+  settleBetIx,
+  settleBet,
+} = require("./helpers/bankrun");
+
+describe("migrate_account", () => {
+  let env, market, otherMarket, better, matcher;
+
+  const migrateAccountIx = (account, accountMarket) =>
+    env.program.methods.migrateAccount().accountsStrict({
+      payer: env.payer.publicKey,
+      account,
+      market: accountMarket ? accountMarket.address : null,
+      systemProgram: SystemProgram.programId,
+    });
+
+  // Rewrite a bet as it was stored before settles_on_round was appended
+  const storeAsV5 = async (bet) => {
+    const account = await env.context.banksClient.getAccount(bet.address);
+    const decoded = env.program.coder.accounts.decode(
+      "Bet",
+      Buffer.from(account.data)
+    );
+    const data = Buffer.alloc(account.data.length - 1);
+    const encoded = await env.program.coder.accounts.encode("Bet", {
+      ...decoded,
+      version: 5,
+    });
+    encoded.copy(data);
+    await setRawAccount(env, bet.address, data);
+    return account.data.length;
+  };
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    otherMarket = await createMarket(env, { index: 1 });
+    better = await wallet(env);
+    matcher = await wallet(env);
+  });
+
+  it("rejects matching a bet in an older layout until it is migrated", async () => {
+    const bet = await createBet(env, market, better, {
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    const len = await storeAsV5(bet);
+
+    await expectError(
+      env,
+      await matchBetIx(env, bet, matcher),
+      [matcher.keypair],
+      "AccountNeedsMigration"
+    );
+
+    await send(env, migrateAccountIx(bet.address, market));
+    const account = await env.context.banksClient.getAccount(bet.address);
+    assert.equal(account.data.length, len);
+    const migrated = await env.program.account.bet.fetch(bet.address);
+    assert.equal(migrated.version, 6);
+    assert.isFalse(migrated.settlesOnRound);
+
+    await matchBet(env, bet, matcher);
+    assert.isTrue((await env.program.account.bet.fetch(bet.address)).isMatched);
+  });
+
+  it("rejects settling a bet in an older layout until it is migrated", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    const bet = await createBet(env, market, better, { settlementTime });
+    await matchBet(env, bet, matcher);
+    await warpTo(env, settlementTime);
+    await setMarketPrice(env, market, 110_000);
+    await storeAsV5(bet);
+
+    await expectError(
+      env,
+      await settleBetIx(env, bet),
+      [],
+      "AccountNeedsMigration"
+    );
+
+    await send(env, migrateAccountIx(bet.address, market));
+    await settleBet(env, bet);
+    const settled = await env.program.account.bet.fetch(bet.address);
+    assert.isTrue(settled.isSettled);
+    assert.isTrue(settled.winner.equals(better.publicKey));
+  });
+
+  it("rejects migrating a bet against another market", async () => {
+    const bet = await createBet(env, market, better, {
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    await storeAsV5(bet);
+
+    await expectError(
+      env,
+      migrateAccountIx(bet.address, otherMarket),
+      [],
+      "InvalidMarket"
+    );
+  });
+
+  it("rejects migrating accounts already on the current layout", async () => {
+    const bet = await createBet(env, market, better, {
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+
+    await expectError(
+      env,
+      migrateAccountIx(bet.address, market),
+      [],
+      "AccountAlreadyMigrated"
+    );
+    await expectError(
+      env,
+      migrateAccountIx(market.address, null),
+      [],
+      "AccountAlreadyMigrated"
+    );
+  });
+});
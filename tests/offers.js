@@ -0,0 +1,197 @@
+const { SystemProgram } = require("@solana/web3.js");
+const { TOKEN_PROGRAM_ID } = require("@solana/spl-token");
+const { assert } = require("chai");
+const {
+  BN,
+  USDC,
+  USDC_MINT,
+  HOUR,
+  setup,
+  send,
+  expectError,
+  now,
+  betPda,
+  profilePda,
+  betIndexPda,
+  offerPda,
+  setTokenAccount,
+  tokenBalance,
+  accountExists,
+  wallet,
+  fetchMarket,
+  fetchProfile,
+  createMarket,
+  updateMarketLimits,
+} = require("./helpers/bankrun");
+
+describe("offers", () => {
+  let env, market, creator, taker, offer, withdrawn;
+  let offerId = 0;
+
+  const createOfferIx = async (capacity, direction = { above: {} }) => {
+    const id = ++offerId;
+    const address = offerPda(env, market.address, creator.publicKey, id);
+    const offerVault = setTokenAccount(env, address, 0);
+    const builder = env.program.methods
+      .createOffer(
+        new BN(id),
+        new BN(capacity * USDC),
+        new BN(100_000),
+        direction,
+        new BN((await now(env)) + 2 * HOUR),
+        ""
+      )
+      .accountsStrict({
+        creator: creator.publicKey,
+        market: market.address,
+        offer: address,
+        usdcMint: USDC_MINT,
+        offerVault,
+        creatorTokenAccount: creator.tokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    return { builder, offer: { address, vault: offerVault } };
+  };
+
+  const createOffer = async (capacity) => {
+    const { builder, offer } = await createOfferIx(capacity);
+    await send(env, builder, [creator.keypair]);
+    return offer;
+  };
+
+  // The bet takes the market's next bet number, like any other
+  const takeOfferIx = async (offer, party, amount) => {
+    const { betCount } = await fetchMarket(env, market);
+    const bet = betPda(env, market.address, betCount.addn(1));
+    const betEscrow = setTokenAccount(env, bet, 0);
+    const creatorProfile = await fetchProfile(env, creator);
+    const takerProfile = await fetchProfile(env, party);
+    const builder = env.program.methods
+      .takeOffer(new BN(amount * USDC))
+      .accountsStrict({
+        taker: party.publicKey,
+        offer: offer.address,
+        market: market.address,
+        bet,
+        usdcMint: USDC_MINT,
+        betEscrow,
+        offerVault: offer.vault,
+        takerTokenAccount: party.tokenAccount,
+        matchFilter: null,
+        creatorProfile: profilePda(env, creator.publicKey),
+        creatorBetIndex: betIndexPda(
+          env,
+          creator.publicKey,
+          creatorProfile.betIndexCount
+        ),
+        takerProfile: profilePda(env, party.publicKey),
+        takerBetIndex: betIndexPda(
+          env,
+          party.publicKey,
+          takerProfile.betIndexCount
+        ),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    return { builder, bet: { address: bet, escrow: betEscrow, market } };
+  };
+
+  const closeOfferIx = (offer) =>
+    env.program.methods.closeOffer().accountsStrict({
+      creator: creator.publicKey,
+      offer: offer.address,
+      market: market.address,
+      offerVault: offer.vault,
+      creatorTokenAccount: creator.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  const openInterest = async () =>
+    (await fetchMarket(env, market)).openInterest.toNumber();
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    creator = await wallet(env);
+    taker = await wallet(env);
+    await updateMarketLimits(env, market, {
+      minStake: USDC,
+      maxStake: 50 * USDC,
+      maxOpenInterest: 100 * USDC,
+    });
+  });
+
+  it("rejects empty, oversized and relative offers", async () => {
+    for (const [capacity, direction, code] of [
+      [0, { above: {} }, "InvalidOfferCapacity"],
+      [60, { above: {} }, "StakeTooLarge"],
+      [10, { upBy: { bps: 500 } }, "UnsupportedOfferDirection"],
+    ]) {
+      const { builder } = await createOfferIx(capacity, direction);
+      await expectError(env, builder, [creator.keypair], code);
+    }
+  });
+
+  it("counts an offer's whole capacity toward the open interest cap", async () => {
+    offer = await createOffer(40);
+    withdrawn = await createOffer(50);
+    assert.equal(await openInterest(), 90 * USDC);
+
+    const { builder } = await createOfferIx(20);
+    await expectError(
+      env,
+      builder,
+      [creator.keypair],
+      "OpenInterestLimitExceeded"
+    );
+    // Taking adds the taker's side on top of the creator's
+    await expectError(
+      env,
+      (await takeOfferIx(offer, taker, 20)).builder,
+      [taker.keypair],
+      "OpenInterestLimitExceeded"
+    );
+  });
+
+  it("refunds and releases an offer's capacity when it is closed", async () => {
+    const { openBetCount } = await fetchMarket(env, market);
+    const balance = await tokenBalance(env, creator.tokenAccount);
+    await send(env, closeOfferIx(withdrawn), [creator.keypair]);
+
+    assert.equal(
+      (await tokenBalance(env, creator.tokenAccount)) - balance,
+      50 * USDC
+    );
+    assert.isFalse(await accountExists(env, withdrawn.vault));
+    assert.isFalse(await accountExists(env, withdrawn.address));
+    assert.equal(await openInterest(), 40 * USDC);
+    assert.equal(
+      (await fetchMarket(env, market)).openBetCount.toNumber(),
+      openBetCount.toNumber() - 1
+    );
+  });
+
+  it("matches takers against the remaining capacity", async () => {
+    const { builder, bet } = await takeOfferIx(offer, taker, 25);
+    await send(env, builder, [taker.keypair]);
+    const betAccount = await env.program.account.bet.fetch(bet.address);
+    assert.isTrue(betAccount.better.equals(creator.publicKey));
+    assert.isTrue(betAccount.matcher.equals(taker.publicKey));
+    assert.equal(await tokenBalance(env, bet.escrow), 50 * USDC);
+    assert.equal(await tokenBalance(env, offer.vault), 15 * USDC);
+    assert.equal(await openInterest(), 65 * USDC);
+
+    await expectError(
+      env,
+      (await takeOfferIx(offer, taker, 20)).builder,
+      [taker.keypair],
+      "OfferCapacityExceeded"
+    );
+
+    // Closing leaves the bet taken from the offer in place
+    await send(env, closeOfferIx(offer), [creator.keypair]);
+    assert.equal(await openInterest(), 50 * USDC);
+    assert.isTrue(await accountExists(env, bet.address));
+  });
+});
@@ -0,0 +1,129 @@
+const { assert } = require("chai");
+const {
+  HOUR,
+  ETH_FEED,
+  setup,
+  expectError,
+  now,
+  warpTo,
+  setMarketPrice,
+  wallet,
+  fetchMarket,
+  createMarket,
+  createBetIx,
+  createBet,
+  matchBetIx,
+  matchBet,
+  settleBetIx,
+  settleBet,
+} = require("./helpers/bankrun");
+
+describe("outperform bets", () => {
+  let env, market, ethMarket, otherMarket, better, matcher;
+
+  const createOutperformBet = async (against = ethMarket) =>
+    createBet(env, market, better, {
+      priceThreshold: 0,
+      direction: { outperform: { otherMarket: against.address } },
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+
+  const openBetCount = async (m) =>
+    (await fetchMarket(env, m)).openBetCount.toNumber();
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    ethMarket = await createMarket(env, {
+      feed: ETH_FEED,
+      tokenName: "ETH",
+      price: 4_000,
+    });
+    otherMarket = await createMarket(env, { index: 1 });
+    better = await wallet(env);
+    matcher = await wallet(env);
+  });
+
+  it("rejects outperforming the bet's own market", async () => {
+    const { builder } = await createBetIx(env, market, better, {
+      priceThreshold: 0,
+      direction: { outperform: { otherMarket: market.address } },
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    await expectError(env, builder, [better.keypair], "InvalidOtherMarket");
+  });
+
+  it("needs the bet's other market to match", async () => {
+    const bet = await createOutperformBet();
+    await expectError(
+      env,
+      await matchBetIx(env, bet, matcher, { withPrice: true }),
+      [matcher.keypair],
+      "InvalidOtherMarket"
+    );
+    await expectError(
+      env,
+      await matchBetIx(env, bet, matcher, { otherMarket }),
+      [matcher.keypair],
+      "InvalidOtherMarket"
+    );
+  });
+
+  it("holds the other market open until the bet settles", async () => {
+    const bet = await createOutperformBet();
+    const before = await openBetCount(ethMarket);
+    await matchBet(env, bet, matcher, { otherMarket: ethMarket });
+    assert.equal(await openBetCount(ethMarket), before + 1);
+
+    const { settlementTime } = await env.program.account.bet.fetch(
+      bet.address
+    );
+    await warpTo(env, settlementTime.toNumber());
+    await setMarketPrice(env, market, 100_000);
+    await setMarketPrice(env, ethMarket, 4_000);
+    await expectError(
+      env,
+      await settleBetIx(env, bet),
+      [],
+      "InvalidOtherMarket"
+    );
+
+    await settleBet(env, bet, { otherMarket: ethMarket });
+    assert.equal(await openBetCount(ethMarket), before);
+  });
+
+  it("pays whoever backed the better relative move", async () => {
+    await setMarketPrice(env, market, 100_000);
+    await setMarketPrice(env, ethMarket, 4_000);
+    const outpaced = await createOutperformBet();
+    await matchBet(env, outpaced, matcher, { otherMarket: ethMarket });
+    const { settlementTime, referencePrice, otherReferencePrice } =
+      await env.program.account.bet.fetch(outpaced.address);
+    assert.equal(referencePrice.toNumber(), 100_000);
+    assert.equal(otherReferencePrice.toNumber(), 4_000);
+
+    // BTC up 10% against ETH up 5%
+    await warpTo(env, settlementTime.toNumber());
+    await setMarketPrice(env, market, 110_000);
+    await setMarketPrice(env, ethMarket, 4_200);
+    await settleBet(env, outpaced, { otherMarket: ethMarket });
+    assert.isTrue(
+      (await env.program.account.bet.fetch(outpaced.address)).winner.equals(
+        better.publicKey
+      )
+    );
+
+    // BTC flat against ETH up 20%
+    const lagged = await createOutperformBet();
+    await matchBet(env, lagged, matcher, { otherMarket: ethMarket });
+    await warpTo(env, (await now(env)) + 2 * HOUR);
+    await setMarketPrice(env, market, 110_000);
+    await setMarketPrice(env, ethMarket, 5_040);
+    await settleBet(env, lagged, { otherMarket: ethMarket });
+    assert.isTrue(
+      (await env.program.account.bet.fetch(lagged.address)).winner.equals(
+        matcher.publicKey
+      )
+    );
+  });
+});
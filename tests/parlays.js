@@ -0,0 +1,283 @@
+const { SystemProgram } = require("@solana/web3.js");
+const { TOKEN_PROGRAM_ID } = require("@solana/spl-token");
+const { assert } = require("chai");
+const {
+  BN,
+  USDC,
+  USDC_MINT,
+  HOUR,
+  ETH_FEED,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  profilePda,
+  matchFilterPda,
+  parlayPda,
+  setTokenAccount,
+  tokenBalance,
+  accountExists,
+  setMarketPrice,
+  wallet,
+  fetchMarket,
+  fetchProfile,
+  createMarket,
+  initializeMatchFilter,
+} = require("./helpers/bankrun");
+
+describe("parlays", () => {
+  let env, btc, eth, filtered, alice, bob, carol;
+  let parlayId = 0;
+
+  const writable = (pubkey) => ({ pubkey, isWritable: true, isSigner: false });
+
+  // An above-`threshold` leg on `market`
+  const leg = (market, threshold, settlementTime) => ({
+    market,
+    priceThreshold: new BN(threshold),
+    priceDirection: { above: {} },
+    settlementTime: new BN(settlementTime),
+  });
+
+  const createParlayIx = (
+    better,
+    legs,
+    { amount = 10, matcherAmount = 10 } = {}
+  ) => {
+    const id = ++parlayId;
+    const parlay = parlayPda(env, better.publicKey, id);
+    const parlayEscrow = setTokenAccount(env, parlay, 0);
+    const builder = env.program.methods
+      .createParlay(
+        new BN(id),
+        new BN(amount * USDC),
+        new BN(matcherAmount * USDC),
+        legs.map(({ market, ...terms }) => ({
+          market: market.address,
+          ...terms,
+        }))
+      )
+      .accountsStrict({
+        better: better.publicKey,
+        parlay,
+        usdcMint: USDC_MINT,
+        parlayEscrow,
+        betterTokenAccount: better.tokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(legs.map(({ market }) => writable(market.address)));
+    return {
+      builder,
+      parlay: {
+        address: parlay,
+        escrow: parlayEscrow,
+        better,
+        markets: legs.map(({ market }) => market),
+      },
+    };
+  };
+
+  const createParlay = async (better, legs, options) => {
+    const { builder, parlay } = createParlayIx(better, legs, options);
+    await send(env, builder, [better.keypair]);
+    return parlay;
+  };
+
+  // Match filters follow the leg markets, one for each market that has one
+  const matchParlayIx = (parlay, matcher, filters = []) =>
+    env.program.methods
+      .matchParlay()
+      .accountsStrict({
+        matcher: matcher.publicKey,
+        parlay: parlay.address,
+        parlayEscrow: parlay.escrow,
+        matcherTokenAccount: matcher.tokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        ...parlay.markets.map((market) => writable(market.address)),
+        ...filters.map((market) => ({
+          pubkey: matchFilterPda(env, market.address),
+          isWritable: false,
+          isSigner: false,
+        })),
+      ]);
+
+  const settleParlayLeg = (parlay, matcher, legIndex) =>
+    send(
+      env,
+      env.program.methods.settleParlayLeg(legIndex).accountsStrict({
+        parlay: parlay.address,
+        market: parlay.markets[legIndex].address,
+        priceUpdate: parlay.markets[legIndex].priceUpdate,
+        settlementRound: null,
+        betterProfile: profilePda(env, parlay.better.publicKey),
+        matcherProfile: profilePda(env, matcher.publicKey),
+      })
+    );
+
+  // Fee accounts follow the leg markets, one for each leg
+  const claimParlayIx = (parlay, claimer, feeAccounts) =>
+    env.program.methods
+      .claimParlay()
+      .accountsStrict({
+        claimer: claimer.publicKey,
+        parlay: parlay.address,
+        parlayEscrow: parlay.escrow,
+        claimerTokenAccount: claimer.tokenAccount,
+        claimerProfile: profilePda(env, claimer.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        ...parlay.markets.map((market) => writable(market.address)),
+        ...feeAccounts.map(writable),
+      ]);
+
+  before(async () => {
+    env = await setup();
+    btc = await createMarket(env);
+    eth = await createMarket(env, {
+      feed: ETH_FEED,
+      tokenName: "ETH",
+      price: 4_000,
+    });
+    filtered = await createMarket(env, { index: 1 });
+    alice = await wallet(env);
+    bob = await wallet(env);
+    carol = await wallet(env);
+    await initializeMatchFilter(env, filtered, { allowlist: {} }, [bob]);
+  });
+
+  it("needs two to eight legs on absolute conditions", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    await expectError(
+      env,
+      createParlayIx(alice, [leg(btc, 100_000, settlementTime)]).builder,
+      [alice.keypair],
+      "InvalidParlayLegs"
+    );
+    await expectError(
+      env,
+      createParlayIx(alice, [
+        leg(btc, 100_000, settlementTime),
+        {
+          ...leg(eth, 0, settlementTime),
+          priceDirection: { upBy: { bps: 500 } },
+        },
+      ]).builder,
+      [alice.keypair],
+      "InvalidParlayLegs"
+    );
+  });
+
+  it("holds the matcher's stake to each leg market's minimum", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    await expectError(
+      env,
+      createParlayIx(
+        alice,
+        [leg(btc, 100_000, settlementTime), leg(eth, 4_000, settlementTime)],
+        { matcherAmount: 0.5 }
+      ).builder,
+      [alice.keypair],
+      "StakeTooSmall"
+    );
+  });
+
+  it("applies each leg market's match filter", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    const parlay = await createParlay(alice, [
+      leg(btc, 100_000, settlementTime),
+      leg(filtered, 100_000, settlementTime),
+    ]);
+
+    await expectError(
+      env,
+      matchParlayIx(parlay, carol),
+      [carol.keypair],
+      "InvalidMatchFilter"
+    );
+    await expectError(
+      env,
+      matchParlayIx(parlay, carol, [filtered]),
+      [carol.keypair],
+      "MatcherNotAllowed"
+    );
+    await expectError(
+      env,
+      matchParlayIx(parlay, alice, [filtered]),
+      [alice.keypair],
+      "SelfMatch"
+    );
+    await send(env, matchParlayIx(parlay, bob, [filtered]), [bob.keypair]);
+    assert.isTrue(
+      (await env.program.account.parlay.fetch(parlay.address)).isMatched
+    );
+  });
+
+  it("pays the better when every leg wins and splits the fee across the legs", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    const parlay = await createParlay(
+      alice,
+      [leg(btc, 100_000, settlementTime), leg(eth, 4_000, settlementTime)],
+      { amount: 10, matcherAmount: 20 }
+    );
+    await send(env, matchParlayIx(parlay, bob), [bob.keypair]);
+    const aliceProfile = await fetchProfile(env, alice);
+    const bobProfile = await fetchProfile(env, bob);
+
+    await warpTo(env, settlementTime);
+    await setMarketPrice(env, btc, 110_000);
+    await setMarketPrice(env, eth, 4_100);
+    await settleParlayLeg(parlay, bob, 0);
+    assert.isFalse(
+      (await env.program.account.parlay.fetch(parlay.address)).isSettled
+    );
+    await settleParlayLeg(parlay, bob, 1);
+    const settled = await env.program.account.parlay.fetch(parlay.address);
+    assert.isTrue(settled.winner.equals(alice.publicKey));
+    assert.equal(
+      (await fetchProfile(env, alice)).wins.toNumber(),
+      aliceProfile.wins.toNumber() + 1
+    );
+    assert.equal(
+      (await fetchProfile(env, bob)).losses.toNumber(),
+      bobProfile.losses.toNumber() + 1
+    );
+
+    const feeAccounts = [btc, eth].map((market) =>
+      setTokenAccount(env, market.feeClaimer.publicKey, 0)
+    );
+    await expectError(
+      env,
+      claimParlayIx(parlay, alice, [alice.tokenAccount, feeAccounts[1]]),
+      [],
+      "InvalidFeeRecipient"
+    );
+
+    const fees = await Promise.all(
+      [btc, eth].map(async (market) =>
+        (await fetchMarket(env, market)).totalFeesCollected.toNumber()
+      )
+    );
+    const balance = await tokenBalance(env, alice.tokenAccount);
+    await send(env, claimParlayIx(parlay, alice, feeAccounts));
+
+    // 3% of the 30 USDC pot, half to each leg's market
+    assert.equal(
+      (await tokenBalance(env, alice.tokenAccount)) - balance,
+      29_100_000
+    );
+    for (const [i, market] of [btc, eth].entries()) {
+      assert.equal(await tokenBalance(env, feeAccounts[i]), 450_000);
+      assert.equal(
+        (await fetchMarket(env, market)).totalFeesCollected.toNumber(),
+        fees[i] + 450_000
+      );
+    }
+    assert.isFalse(await accountExists(env, parlay.escrow));
+    assert.isFalse(await accountExists(env, parlay.address));
+  });
+});
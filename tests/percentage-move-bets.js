@@ -0,0 +1,85 @@
+const { assert } = require("chai");
+const {
+  HOUR,
+  setup,
+  expectError,
+  now,
+  warpTo,
+  setMarketPrice,
+  wallet,
+  createMarket,
+  createBetIx,
+  createBet,
+  matchBetIx,
+  matchBet,
+  settleBet,
+} = require("./helpers/bankrun");
+
+describe("percentage-move bets", () => {
+  let env, market, better, matcher;
+
+  const createMoveBet = async (direction) =>
+    createBet(env, market, better, {
+      priceThreshold: 0,
+      direction,
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    better = await wallet(env);
+    matcher = await wallet(env);
+  });
+
+  it("rejects moves of zero or a full 100% down", async () => {
+    for (const direction of [
+      { upBy: { bps: 0 } },
+      { downBy: { bps: 10_000 } },
+    ]) {
+      const { builder } = await createBetIx(env, market, better, {
+        priceThreshold: 0,
+        direction,
+        settlementTime: (await now(env)) + 2 * HOUR,
+      });
+      await expectError(env, builder, [better.keypair], "InvalidPriceMove");
+    }
+  });
+
+  it("needs the price update to match", async () => {
+    const bet = await createMoveBet({ upBy: { bps: 500 } });
+    await expectError(
+      env,
+      await matchBetIx(env, bet, matcher),
+      [matcher.keypair],
+      "PriceFeedLoadError"
+    );
+  });
+
+  it("settles against the price recorded at match time", async () => {
+    await setMarketPrice(env, market, 100_000);
+    const up = await createMoveBet({ upBy: { bps: 500 } });
+    const down = await createMoveBet({ downBy: { bps: 500 } });
+    await matchBet(env, up, matcher, { withPrice: true });
+    await matchBet(env, down, matcher, { withPrice: true });
+    const { referencePrice, settlementTime } =
+      await env.program.account.bet.fetch(up.address);
+    assert.equal(referencePrice.toNumber(), 100_000);
+
+    await warpTo(env, settlementTime.toNumber());
+    // Exactly 5% up wins the up bet; the down bet needed 5% down
+    await setMarketPrice(env, market, 105_000);
+    await settleBet(env, up);
+    await settleBet(env, down);
+    assert.isTrue(
+      (await env.program.account.bet.fetch(up.address)).winner.equals(
+        better.publicKey
+      )
+    );
+    assert.isTrue(
+      (await env.program.account.bet.fetch(down.address)).winner.equals(
+        matcher.publicKey
+      )
+    );
+  });
+});
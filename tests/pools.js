@@ -0,0 +1,246 @@
+const { SystemProgram } = require("@solana/web3.js");
+const { TOKEN_PROGRAM_ID } = require("@solana/spl-token");
+const { assert } = require("chai");
+const {
+  BN,
+  USDC,
+  USDC_MINT,
+  HOUR,
+  VOID_DELAY,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  profilePda,
+  roundPda,
+  poolPda,
+  poolPositionPda,
+  setTokenAccount,
+  tokenBalance,
+  wallet,
+  fetchMarket,
+  createMarket,
+  updateSettlementBounds,
+  recordSettlementRound,
+} = require("./helpers/bankrun");
+
+const YES = { yes: {} };
+const NO = { no: {} };
+
+describe("pools", () => {
+  let env, market, alice, bob, carol;
+
+  const createPoolIx = (settlementTime, direction = { above: {} }) => {
+    const pool = poolPda(
+      env,
+      market.address,
+      settlementTime,
+      100_000,
+      direction
+    );
+    const poolEscrow = setTokenAccount(env, pool, 0);
+    const builder = env.program.methods
+      .createPool(new BN(settlementTime), new BN(100_000), direction)
+      .accountsStrict({
+        payer: env.payer.publicKey,
+        market: market.address,
+        pool,
+        usdcMint: USDC_MINT,
+        poolEscrow,
+        systemProgram: SystemProgram.programId,
+      });
+    return { builder, pool: { address: pool, escrow: poolEscrow } };
+  };
+
+  // An above-100k pool on the slot two hours out
+  const createPool = async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    const { builder, pool } = createPoolIx(settlementTime);
+    await send(env, builder);
+    return { ...pool, settlementTime };
+  };
+
+  const enterPoolIx = async (pool, owner, side, amount) => {
+    const { positionCount } = await env.program.account.pool.fetch(
+      pool.address
+    );
+    const position = poolPositionPda(env, pool.address, positionCount.addn(1));
+    const builder = env.program.methods
+      .enterPool(side, new BN(amount * USDC))
+      .accountsStrict({
+        owner: owner.publicKey,
+        market: market.address,
+        pool: pool.address,
+        poolEscrow: pool.escrow,
+        ownerTokenAccount: owner.tokenAccount,
+        position,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    return { builder, position };
+  };
+
+  const enterPool = async (pool, owner, side, amount) => {
+    const { builder, position } = await enterPoolIx(pool, owner, side, amount);
+    await send(env, builder, [owner.keypair]);
+    return position;
+  };
+
+  const settlePoolIx = (pool) =>
+    env.program.methods.settlePool().accountsStrict({
+      market: market.address,
+      pool: pool.address,
+      settlementRound: roundPda(env, market.address, pool.settlementTime),
+    });
+
+  const voidPoolIx = (pool) =>
+    env.program.methods.voidPool().accountsStrict({
+      authority: env.payer.publicKey,
+      market: market.address,
+      pool: pool.address,
+    });
+
+  // Losing positions can be closed without either token account
+  const claimPoolIx = (
+    pool,
+    position,
+    owner,
+    { paid = false, feeRecipient = null } = {}
+  ) =>
+    env.program.methods.claimPool().accountsStrict({
+      owner: owner.publicKey,
+      position,
+      pool: pool.address,
+      market: market.address,
+      poolEscrow: pool.escrow,
+      ownerTokenAccount: paid ? owner.tokenAccount : null,
+      feeRecipientTokenAccount: feeRecipient,
+      ownerProfile: profilePda(env, owner.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    alice = await wallet(env);
+    bob = await wallet(env);
+    carol = await wallet(env);
+    await updateSettlementBounds(env, market, { interval: HOUR });
+  });
+
+  it("only opens pools for absolute conditions on a settlement slot", async () => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    await expectError(
+      env,
+      createPoolIx(settlementTime + 60).builder,
+      [],
+      "SettlementTimeNotOnSlot"
+    );
+    await expectError(
+      env,
+      createPoolIx(settlementTime, { upBy: { bps: 500 } }).builder,
+      [],
+      "UnsupportedPoolDirection"
+    );
+  });
+
+  it("splits the losing side between the winners and sweeps the dust", async () => {
+    const pool = await createPool();
+    const alicePosition = await enterPool(pool, alice, YES, 10);
+    const bobPosition = await enterPool(pool, bob, YES, 20);
+    const carolPosition = await enterPool(pool, carol, NO, 10);
+    const feeRecipient = setTokenAccount(env, market.feeClaimer.publicKey, 0);
+    const { openBetCount } = await fetchMarket(env, market);
+
+    await warpTo(env, pool.settlementTime);
+    await expectError(
+      env,
+      (await enterPoolIx(pool, carol, NO, 10)).builder,
+      [carol.keypair],
+      "BetExpired"
+    );
+    await expectError(
+      env,
+      claimPoolIx(pool, carolPosition, carol),
+      [],
+      "PoolNotSettled"
+    );
+    await recordSettlementRound(env, market, pool.settlementTime, 105_000);
+    await send(env, settlePoolIx(pool));
+    assert.deepEqual(
+      (await env.program.account.pool.fetch(pool.address)).winningSide,
+      YES
+    );
+
+    const carolBalance = await tokenBalance(env, carol.tokenAccount);
+    await send(env, claimPoolIx(pool, carolPosition, carol));
+    assert.equal(await tokenBalance(env, carol.tokenAccount), carolBalance);
+
+    await expectError(
+      env,
+      claimPoolIx(pool, alicePosition, alice),
+      [],
+      "TokenAccountRequired"
+    );
+    // 10 + 10/30 of 10 and 20 + 20/30 of 10 USDC, less the 3% fee
+    const aliceBalance = await tokenBalance(env, alice.tokenAccount);
+    const bobBalance = await tokenBalance(env, bob.tokenAccount);
+    for (const [position, owner] of [
+      [alicePosition, alice],
+      [bobPosition, bob],
+    ]) {
+      await send(
+        env,
+        claimPoolIx(pool, position, owner, { paid: true, feeRecipient })
+      );
+    }
+    assert.equal(
+      (await tokenBalance(env, alice.tokenAccount)) - aliceBalance,
+      12_933_334
+    );
+    assert.equal(
+      (await tokenBalance(env, bob.tokenAccount)) - bobBalance,
+      25_866_667
+    );
+
+    // The last claim sweeps the unallocated micro-USDC and closes the escrow
+    assert.equal(await tokenBalance(env, feeRecipient), 399_999 + 799_999 + 1);
+    assert.isNull(await tokenBalance(env, pool.escrow));
+    assert.equal(
+      (await fetchMarket(env, market)).openBetCount.toNumber(),
+      openBetCount.toNumber() - 3
+    );
+  });
+
+  it("refunds every position once a pool without a round is voided", async () => {
+    const pool = await createPool();
+    const alicePosition = await enterPool(pool, alice, YES, 10);
+    const bobPosition = await enterPool(pool, bob, NO, 15);
+
+    await warpTo(env, pool.settlementTime + VOID_DELAY - 1);
+    await expectError(env, voidPoolIx(pool), [], "PoolVoidTooEarly");
+    await warpTo(env, pool.settlementTime + VOID_DELAY);
+    await send(env, voidPoolIx(pool));
+    await expectError(env, voidPoolIx(pool), [], "PoolAlreadySettled");
+
+    // Refunds are free, so no fee account is needed
+    const aliceBalance = await tokenBalance(env, alice.tokenAccount);
+    const bobBalance = await tokenBalance(env, bob.tokenAccount);
+    for (const [position, owner] of [
+      [alicePosition, alice],
+      [bobPosition, bob],
+    ]) {
+      await send(env, claimPoolIx(pool, position, owner, { paid: true }));
+    }
+    assert.equal(
+      (await tokenBalance(env, alice.tokenAccount)) - aliceBalance,
+      10 * USDC
+    );
+    assert.equal(
+      (await tokenBalance(env, bob.tokenAccount)) - bobBalance,
+      15 * USDC
+    );
+    assert.isNull(await tokenBalance(env, pool.escrow));
+  });
+});
@@ -0,0 +1,76 @@
+const { assert } = require("chai");
+const {
+  BN,
+  HOUR,
+  setup,
+  expectError,
+  now,
+  warpTo,
+  setMarketPrice,
+  wallet,
+  createMarket,
+  createBetIx,
+  createBet,
+  matchBet,
+  settleBet,
+} = require("./helpers/bankrun");
+
+describe("range bets", () => {
+  let env, market, better, matcher;
+
+  const range = (low, high) => ({ low: new BN(low), high: new BN(high) });
+
+  // Create and match a bet on `direction`, then settle it at `price`
+  const settleAt = async (direction, price) => {
+    const settlementTime = (await now(env)) + 2 * HOUR;
+    const bet = await createBet(env, market, better, {
+      priceThreshold: 0,
+      direction,
+      settlementTime,
+    });
+    await matchBet(env, bet, matcher);
+    await warpTo(env, settlementTime);
+    await setMarketPrice(env, market, price);
+    await settleBet(env, bet);
+    return env.program.account.bet.fetch(bet.address);
+  };
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    better = await wallet(env);
+    matcher = await wallet(env);
+  });
+
+  it("rejects ranges whose low end isn't below the high end", async () => {
+    for (const direction of [
+      { between: range(100_000, 100_000) },
+      { outside: range(105_000, 95_000) },
+    ]) {
+      const { builder } = await createBetIx(env, market, better, {
+        priceThreshold: 0,
+        direction,
+        settlementTime: (await now(env)) + 2 * HOUR,
+      });
+      await expectError(env, builder, [better.keypair], "InvalidPriceRange");
+    }
+  });
+
+  it("pays a between bet to the better inside the range, ends included", async () => {
+    for (const price of [95_000, 100_000, 105_000]) {
+      const bet = await settleAt({ between: range(95_000, 105_000) }, price);
+      assert.isTrue(bet.winner.equals(better.publicKey));
+    }
+    const bet = await settleAt({ between: range(95_000, 105_000) }, 105_001);
+    assert.isTrue(bet.winner.equals(matcher.publicKey));
+  });
+
+  it("pays an outside bet to the better only outside the range", async () => {
+    for (const price of [94_999, 105_001]) {
+      const bet = await settleAt({ outside: range(95_000, 105_000) }, price);
+      assert.isTrue(bet.winner.equals(better.publicKey));
+    }
+    const bet = await settleAt({ outside: range(95_000, 105_000) }, 95_000);
+    assert.isTrue(bet.winner.equals(matcher.publicKey));
+  });
+});
@@ -0,0 +1,256 @@
+const { SystemProgram } = require("@solana/web3.js");
+const { TOKEN_PROGRAM_ID } = require("@solana/spl-token");
+const { assert } = require("chai");
+const {
+  BN,
+  USDC,
+  USDC_MINT,
+  HOUR,
+  DAY,
+  ROLLOVER_DELAY,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  betPda,
+  profilePda,
+  betIndexPda,
+  rolloverAuthorityPda,
+  setTokenAccount,
+  tokenBalance,
+  wallet,
+  fetchMarket,
+  fetchProfile,
+  createMarket,
+  updateSettlementBounds,
+  createBet,
+  matchBetIx,
+  matchBet,
+  settleBet,
+  claimFundsIx,
+  recordSettlementRound,
+} = require("./helpers/bankrun");
+
+describe("recurring bets", () => {
+  let env, market, better, matcher, stranger, recurring, nextRound;
+
+  const setBetRecurrenceIx = (bet, interval, rounds) =>
+    env.program.methods
+      .setBetRecurrence(new BN(interval), rounds)
+      .accountsStrict({
+        better: better.publicKey,
+        bet: bet.address,
+        market: market.address,
+      });
+
+  const stopBetRecurrenceIx = (bet, party) =>
+    env.program.methods.stopBetRecurrence().accountsStrict({
+      party: party.publicKey,
+      bet: bet.address,
+    });
+
+  // Both parties' stakes come from the accounts they delegated to their
+  // rollover PDA
+  const rollOverBetIx = async (bet) => {
+    const { betCount } = await fetchMarket(env, market);
+    const nextBet = betPda(env, market.address, betCount.addn(1));
+    const nextBetEscrow = setTokenAccount(env, nextBet, 0);
+    const betterProfile = await fetchProfile(env, better);
+    const matcherProfile = await fetchProfile(env, matcher);
+    const builder = env.program.methods.rollOverBet().accountsStrict({
+      payer: env.payer.publicKey,
+      bet: bet.address,
+      market: market.address,
+      nextBet,
+      usdcMint: USDC_MINT,
+      nextBetEscrow,
+      betterTokenAccount: better.rolloverAccount,
+      matcherTokenAccount: matcher.rolloverAccount,
+      betterRolloverAuthority: rolloverAuthorityPda(env, better.publicKey),
+      matcherRolloverAuthority: rolloverAuthorityPda(env, matcher.publicKey),
+      betterProfile: profilePda(env, better.publicKey),
+      betterBetIndex: betIndexPda(
+        env,
+        better.publicKey,
+        betterProfile.betIndexCount
+      ),
+      matcherProfile: profilePda(env, matcher.publicKey),
+      matcherBetIndex: betIndexPda(
+        env,
+        matcher.publicKey,
+        matcherProfile.betIndexCount
+      ),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+    return {
+      builder,
+      nextBet: { address: nextBet, escrow: nextBetEscrow, market },
+    };
+  };
+
+  const settleOnRound = async (bet, price) => {
+    const { settlementTime } = await env.program.account.bet.fetch(
+      bet.address
+    );
+    await warpTo(env, settlementTime.toNumber());
+    await recordSettlementRound(env, market, settlementTime.toNumber(), price);
+    await settleBet(env, bet, { onRound: true });
+  };
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    await updateSettlementBounds(env, market, {
+      minDuration: 2 * HOUR,
+      interval: HOUR,
+    });
+    better = await wallet(env);
+    matcher = await wallet(env);
+    stranger = await wallet(env);
+    for (const party of [better, matcher]) {
+      party.rolloverAccount = setTokenAccount(
+        env,
+        party.publicKey,
+        100 * USDC,
+        {
+          delegate: rolloverAuthorityPda(env, party.publicKey),
+          delegatedAmount: 50 * USDC,
+        }
+      );
+    }
+  });
+
+  it("rejects recurrences the market wouldn't accept", async () => {
+    const bet = await createBet(env, market, better, {
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    for (const [interval, rounds, code] of [
+      [0, 2, "InvalidRecurrence"],
+      [DAY, 0, "InvalidRecurrence"],
+      // The next round would be due only an hour after it can be rolled over
+      [2 * HOUR, 2, "SettlementTimeTooClose"],
+      [91 * DAY, 2, "SettlementTimeTooFar"],
+      [DAY + 60, 2, "SettlementTimeNotOnSlot"],
+    ]) {
+      await expectError(
+        env,
+        setBetRecurrenceIx(bet, interval, rounds),
+        [better.keypair],
+        code
+      );
+    }
+  });
+
+  it("makes matchers agree to the recurrence", async () => {
+    recurring = await createBet(env, market, better, {
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    await send(env, setBetRecurrenceIx(recurring, DAY, 2), [better.keypair]);
+
+    for (const expected of [
+      {},
+      { expectedRecurrenceInterval: DAY, expectedRemainingRounds: 1 },
+    ]) {
+      await expectError(
+        env,
+        await matchBetIx(env, recurring, matcher, expected),
+        [matcher.keypair],
+        "RecurrenceMismatch"
+      );
+    }
+    await matchBet(env, recurring, matcher, {
+      expectedRecurrenceInterval: DAY,
+      expectedRemainingRounds: 2,
+    });
+    await expectError(
+      env,
+      setBetRecurrenceIx(recurring, DAY, 3),
+      [better.keypair],
+      "BetAlreadyMatched"
+    );
+  });
+
+  it("holds the winnings until the next round is rolled over", async () => {
+    await settleOnRound(recurring, 110_000);
+    await expectError(
+      env,
+      claimFundsIx(env, recurring, better),
+      [],
+      "RolloverPending"
+    );
+
+    const { settlementTime } = await env.program.account.bet.fetch(
+      recurring.address
+    );
+    await warpTo(env, settlementTime.toNumber() + ROLLOVER_DELAY - 1);
+    await expectError(
+      env,
+      (await rollOverBetIx(recurring)).builder,
+      [],
+      "RolloverTooEarly"
+    );
+
+    await warpTo(env, settlementTime.toNumber() + ROLLOVER_DELAY);
+    const { builder, nextBet } = await rollOverBetIx(recurring);
+    await send(env, builder);
+    nextRound = nextBet;
+
+    const next = await env.program.account.bet.fetch(nextRound.address);
+    assert.equal(
+      next.settlementTime.toNumber(),
+      settlementTime.toNumber() + DAY
+    );
+    assert.equal(next.remainingRounds, 1);
+    assert.isTrue(next.isMatched);
+    assert.isTrue(next.matcher.equals(matcher.publicKey));
+    assert.equal(await tokenBalance(env, nextRound.escrow), 20 * USDC);
+    for (const party of [better, matcher]) {
+      assert.equal(await tokenBalance(env, party.rolloverAccount), 90 * USDC);
+    }
+
+    // The settled round can be claimed once it has been rolled over
+    const balance = await tokenBalance(env, better.tokenAccount);
+    await send(env, claimFundsIx(env, recurring, better));
+    assert.equal(
+      (await tokenBalance(env, better.tokenAccount)) - balance,
+      19_400_000
+    );
+  });
+
+  it("lets either party stop the series", async () => {
+    await expectError(
+      env,
+      stopBetRecurrenceIx(nextRound, stranger),
+      [stranger.keypair],
+      "NotBetParty"
+    );
+    await expectError(
+      env,
+      (await rollOverBetIx(nextRound)).builder,
+      [],
+      "BetNotSettled"
+    );
+    await send(env, stopBetRecurrenceIx(nextRound, matcher), [
+      matcher.keypair,
+    ]);
+    await expectError(
+      env,
+      stopBetRecurrenceIx(nextRound, better),
+      [better.keypair],
+      "BetDoesNotRecur"
+    );
+
+    await settleOnRound(nextRound, 90_000);
+    await warpTo(env, (await now(env)) + ROLLOVER_DELAY);
+    await expectError(
+      env,
+      (await rollOverBetIx(nextRound)).builder,
+      [],
+      "BetDoesNotRecur"
+    );
+    await send(env, claimFundsIx(env, nextRound, matcher));
+    assert.isNull(await tokenBalance(env, nextRound.escrow));
+  });
+});
@@ -0,0 +1,120 @@
+const { assert } = require("chai");
+const {
+  BN,
+  HOUR,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  profilePda,
+  priceUpdate,
+  wallet,
+  createMarket,
+  createBetIx,
+  createBet,
+  matchBet,
+} = require("./helpers/bankrun");
+
+describe("touch bets", () => {
+  let env, market, better, matcher;
+
+  const triggerBarrierIx = (bet, update) =>
+    env.program.methods.triggerBarrier().accountsStrict({
+      bet: bet.address,
+      market: market.address,
+      priceUpdate: update,
+      betterProfile: profilePda(env, better.publicKey),
+      matcherProfile: profilePda(env, matcher.publicKey),
+      sponsorProfile: null,
+    });
+
+  const createMatchedBet = async (direction, priceThreshold = 0) => {
+    const bet = await createBet(env, market, better, {
+      priceThreshold,
+      direction,
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    await matchBet(env, bet, matcher);
+    return bet;
+  };
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    better = await wallet(env);
+    matcher = await wallet(env);
+  });
+
+  it("rejects a zero barrier", async () => {
+    const { builder } = await createBetIx(env, market, better, {
+      priceThreshold: 0,
+      direction: { touchAbove: { barrier: new BN(0) } },
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    await expectError(env, builder, [better.keypair], "InvalidPriceThreshold");
+  });
+
+  it("only triggers touch bets", async () => {
+    const bet = await createMatchedBet({ above: {} }, 100_000);
+    const update = priceUpdate(env, market, 200_000, await now(env));
+    await expectError(env, triggerBarrierIx(bet, update), [], "NotTouchBet");
+  });
+
+  it("rejects prices from outside the bet's window", async () => {
+    const matchedAt = await now(env);
+    const bet = await createMatchedBet({
+      touchAbove: { barrier: new BN(110_000) },
+    });
+    const { settlementTime } = await env.program.account.bet.fetch(
+      bet.address
+    );
+    for (const publishTime of [matchedAt - 1, settlementTime.toNumber() + 1]) {
+      await warpTo(env, Math.max(publishTime, await now(env)));
+      const update = priceUpdate(env, market, 120_000, publishTime);
+      await expectError(
+        env,
+        triggerBarrierIx(bet, update),
+        [],
+        "PriceOutsideBetWindow"
+      );
+    }
+  });
+
+  it("rejects prices that didn't reach the barrier or aren't fully verified", async () => {
+    const bet = await createMatchedBet({
+      touchBelow: { barrier: new BN(90_000) },
+    });
+    await expectError(
+      env,
+      triggerBarrierIx(bet, priceUpdate(env, market, 90_001, await now(env))),
+      [],
+      "BarrierNotCrossed"
+    );
+    await expectError(
+      env,
+      triggerBarrierIx(
+        bet,
+        priceUpdate(env, market, 80_000, await now(env), { verified: false })
+      ),
+      [],
+      "UnverifiedPriceUpdate"
+    );
+  });
+
+  it("settles for the better as soon as the barrier is touched", async () => {
+    const bet = await createMatchedBet({
+      touchAbove: { barrier: new BN(110_000) },
+    });
+    await warpTo(env, (await now(env)) + HOUR);
+    await send(
+      env,
+      triggerBarrierIx(bet, priceUpdate(env, market, 110_000, await now(env)))
+    );
+
+    const settled = await env.program.account.bet.fetch(bet.address);
+    assert.isTrue(settled.isSettled);
+    assert.isTrue(settled.winner.equals(better.publicKey));
+    assert.isBelow(await now(env), settled.settlementTime.toNumber());
+  });
+});
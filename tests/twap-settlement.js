@@ -0,0 +1,199 @@
+const { assert } = require("chai");
+const {
+  HOUR,
+  setup,
+  send,
+  expectError,
+  now,
+  warpTo,
+  roundPda,
+  priceUpdate,
+  wallet,
+  createMarket,
+  updateSettlementBounds,
+  updatePriceSourceIx,
+  createBet,
+  matchBet,
+  settleBetIx,
+  settleBet,
+  recordSettlementRoundIx,
+  recordSettlementRound,
+} = require("./helpers/bankrun");
+
+// Three samples across a ten minute window, at least 200 seconds apart
+const WINDOW = 600;
+const MIN_SAMPLES = 3;
+const SPOT = { spot: {} };
+
+describe("TWAP settlement", () => {
+  let env, market, spotMarket, better, matcher;
+
+  const addSettlementSampleIx = (settlementTime, update) =>
+    env.program.methods.addSettlementSample().accountsStrict({
+      market: market.address,
+      settlementRound: roundPda(env, market.address, settlementTime),
+      priceUpdate: update,
+    });
+
+  const addSample = (settlementTime, price, publishTime) =>
+    send(
+      env,
+      addSettlementSampleIx(
+        settlementTime,
+        priceUpdate(env, market, price, publishTime)
+      )
+    );
+
+  const fetchRound = (settlementTime) =>
+    env.program.account.settlementRound.fetch(
+      roundPda(env, market.address, settlementTime)
+    );
+
+  before(async () => {
+    env = await setup();
+    market = await createMarket(env);
+    spotMarket = await createMarket(env, { index: 1 });
+    better = await wallet(env);
+    matcher = await wallet(env);
+    await updateSettlementBounds(env, market, { interval: HOUR });
+    await updateSettlementBounds(env, spotMarket, { interval: HOUR });
+  });
+
+  it("rejects a window without samples or samples without a window", async () => {
+    for (const [window, minSamples] of [
+      [WINDOW, 0],
+      [0, MIN_SAMPLES],
+    ]) {
+      await expectError(
+        env,
+        updatePriceSourceIx(env, market, SPOT, window, minSamples),
+        [],
+        "InvalidTwapSettings"
+      );
+    }
+  });
+
+  it("won't change how a market settles while it has open bets", async () => {
+    await createBet(env, spotMarket, better, {
+      settlementTime: (await now(env)) + 2 * HOUR,
+    });
+    await expectError(
+      env,
+      updatePriceSourceIx(env, spotMarket, SPOT, WINDOW, MIN_SAMPLES),
+      [],
+      "MarketHasOpenBets"
+    );
+  });
+
+  it("only takes extra samples on averaged markets", async () => {
+    const settlementTime = (await now(env)) + HOUR;
+    await warpTo(env, settlementTime);
+    await recordSettlementRound(env, spotMarket, settlementTime, 100_000);
+    await expectError(
+      env,
+      env.program.methods.addSettlementSample().accountsStrict({
+        market: spotMarket.address,
+        settlementRound: roundPda(env, spotMarket.address, settlementTime),
+        priceUpdate: priceUpdate(env, spotMarket, 100_000, settlementTime),
+      }),
+      [],
+      "TwapDisabled"
+    );
+  });
+
+  it("opens rounds only on a price from just after the slot", async () => {
+    await send(
+      env,
+      updatePriceSourceIx(env, market, SPOT, WINDOW, MIN_SAMPLES)
+    );
+    const settlementTime = (await now(env)) + HOUR;
+    await expectError(
+      env,
+      recordSettlementRoundIx(
+        env,
+        market,
+        settlementTime,
+        priceUpdate(env, market, 100_000, await now(env))
+      ),
+      [],
+      "SettlementTimeTooEarly"
+    );
+
+    await warpTo(env, settlementTime + 120);
+    for (const [publishTime, code] of [
+      [settlementTime - 1, "PriceBeforeSettlementSlot"],
+      [settlementTime + 61, "PriceAfterSettlementSlot"],
+    ]) {
+      await expectError(
+        env,
+        recordSettlementRoundIx(
+          env,
+          market,
+          settlementTime,
+          priceUpdate(env, market, 100_000, publishTime)
+        ),
+        [],
+        code
+      );
+    }
+  });
+
+  it("settles on the average of samples spread across the window", async () => {
+    const settlementTime = (await now(env)) - 120 + 2 * HOUR;
+    const bet = await createBet(env, market, better, {
+      priceThreshold: 102_000,
+      settlementTime,
+    });
+    await matchBet(env, bet, matcher);
+
+    await warpTo(env, settlementTime);
+    await recordSettlementRound(env, market, settlementTime, 100_000);
+    await expectError(
+      env,
+      await settleBetIx(env, bet, { onRound: true }),
+      [],
+      "TwapIncomplete"
+    );
+
+    await warpTo(env, settlementTime + 400);
+    for (const publishTime of [settlementTime + 199, settlementTime + 601]) {
+      await expectError(
+        env,
+        addSettlementSampleIx(
+          settlementTime,
+          priceUpdate(env, market, 103_000, publishTime)
+        ),
+        [],
+        "InvalidTwapSample"
+      );
+    }
+    await addSample(settlementTime, 103_000, settlementTime + 200);
+    await addSample(settlementTime, 106_000, settlementTime + 400);
+    await expectError(
+      env,
+      addSettlementSampleIx(
+        settlementTime,
+        priceUpdate(env, market, 106_000, settlementTime + 600)
+      ),
+      [],
+      "SettlementRoundComplete"
+    );
+
+    const round = await fetchRound(settlementTime);
+    assert.equal(round.sampleCount, MIN_SAMPLES);
+    assert.equal(round.price.toNumber(), 103_000);
+
+    // The slot price alone was below the threshold, but the average is above it
+    await warpTo(env, settlementTime + WINDOW - 1);
+    await expectError(
+      env,
+      await settleBetIx(env, bet, { onRound: true }),
+      [],
+      "TwapIncomplete"
+    );
+    await warpTo(env, settlementTime + WINDOW);
+    await settleBet(env, bet, { onRound: true });
+    const settled = await env.program.account.bet.fetch(bet.address);
+    assert.isTrue(settled.winner.equals(better.publicKey));
+  });
+});